@@ -14,7 +14,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	let _ = dotenvy::dotenv();
 	let api = Api::new(Auth {
 		uri: "https://api.openai.com/v1".into(),
-		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed"),
+		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed").into(),
+		..Default::default()
 	});
 	let res = api
 		.upload_file(
@@ -25,7 +26,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 					method: Default::default(),
 					url: Endpoint::Embeddings,
 					body: EmbeddingRequest {
-						input: Either::A("Foo".into()),
+						input: "Foo".into(),
 						model: Model::TextEmbedding3Large,
 						..Default::default()
 					},
@@ -35,7 +36,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 					method: Default::default(),
 					url: Endpoint::Embeddings,
 					body: EmbeddingRequest {
-						input: Either::A("Bar".into()),
+						input: "Bar".into(),
 						model: Model::TextEmbedding3Large,
 						..Default::default()
 					},
@@ -48,12 +49,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			.as_bytes()
 			.to_vec(),
 			Purpose::Batch,
+			None,
 		)
 		.await;
 
 	println!("{res:#?}");
 
-	let req = api.retrieve_file_content("file-8TSU8J5RNWNHWnmjKyFGFe2b").await;
 	let req = BatchRequest {
 		endpoint: Endpoint::Embeddings,
 		input_file_id: res?.id,
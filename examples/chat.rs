@@ -19,16 +19,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	let _ = dotenvy::dotenv();
 	let api = Api::new(Auth {
 		uri: "https://openrouter.ai/api/v1".into(),
-		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed"),
+		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed").into(),
+		..Default::default()
 	});
 	let req = ChatRequest {
 		messages: vec![
 			ChatMessage::System(ChatMessageCommon {
-				content: Either::A("You're a helpful assistant.".into()),
+				content: "You're a helpful assistant.".into(),
 				name: None,
 			}),
 			ChatMessage::User(ChatMessageCommon {
-				content: Either::A("What is the capital of France?".into()),
+				content: "What is the capital of France?".into(),
 				name: None,
 			}),
 		],
@@ -56,12 +57,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 				match event_result {
 					Ok(chunk) =>
 						for choice in chunk.choices {
-							if let Some(delta) = &choice.delta {
-								if let Some(content) = &delta.content {
-									print!("{content}");
+							if let Some(delta) = &choice.delta
+								&& let Some(content) = &delta.content
+							{
+								print!("{content}");
 
-									io::stdout().flush()?;
-								}
+								io::stdout().flush()?;
 							}
 						},
 					Err(e) => {
@@ -14,10 +14,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	let _ = dotenvy::dotenv();
 	let api = Api::new(Auth {
 		uri: env::var("OPENAI_BASE_URL").expect("OPENAI_BASE_URL must be set; qed"),
-		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed"),
+		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed").into(),
+		..Default::default()
 	});
 	let req = EmbeddingRequest {
-		input: Either::A("Hello, how are you?".into()),
+		input: "Hello, how are you?".into(),
 		model: Model::Unknown("Qwen/Qwen3-Embedding-4B".into()),
 		encoding_format: Some(EncodingFormat::Float),
 		..Default::default()
@@ -4,11 +4,7 @@
 use std::{env, error::Error};
 // crates.io
 use futures::StreamExt;
-use rmcp::{
-	ServiceExt,
-	model::{ClientInfo, Implementation},
-	transport::SseClientTransport,
-};
+use rmcp::transport::SseClientTransport;
 use tracing_subscriber::EnvFilter;
 // self
 use openagent::prelude::*;
@@ -20,30 +16,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	let _ = dotenvy::dotenv();
 	let api = Api::new(Auth {
 		uri: "https://api.openai.com/v1".into(),
-		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed"),
+		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed").into(),
+		..Default::default()
 	});
 	let transport = SseClientTransport::start("http://0.0.0.0:8000/sse").await?;
-	let mcp_info = ClientInfo {
-		protocol_version: Default::default(),
-		capabilities: Default::default(),
-		client_info: Implementation { name: "postgresql".into(), version: "0.1.0".into() },
-	};
-	let mcp = mcp_info.serve(transport).await?;
-	let tools = mcp.list_tools(Default::default()).await?;
-	let tools = tools
-		.tools
-		.into_iter()
-		.map(|t| {
-			let parameters = t.schema_as_json_value();
-
-			Tool::Function {
-				name: t.name.into_owned(),
-				parameters,
-				strict: Default::default(),
-				description: t.description.map(|d| d.into_owned()),
-			}
-		})
-		.collect();
+	let mcp = McpToolset::connect(transport).await?;
+	let tools = mcp.function_tools();
 
 	// println!("available tools: {tools:?}");
 
@@ -120,7 +98,5 @@ async fn main() -> Result<(), Box<dyn Error>> {
 		},
 	}
 
-	mcp.cancel().await?;
-
 	Ok(())
 }
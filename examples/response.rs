@@ -15,10 +15,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 	let _ = dotenvy::dotenv();
 	let api = Api::new(Auth {
 		uri: "https://api.openai.com/v1".into(),
-		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed"),
+		key: env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY must be set; qed").into(),
+		..Default::default()
 	});
 	let req = ResponseRequest {
-		input: Either::A("Hello, how are you?".into()),
+		input: "Hello, how are you?".into(),
 		model: Model::Gpt4oMini,
 		// model: Model::Custom {
 		// 	id: "".into(),
@@ -1,23 +1,45 @@
+//! ReAct agent.
+
+mod guardrail;
+pub use guardrail::*;
+
+mod hook;
+use hook::AgentHooks;
+
+mod memory;
+pub use memory::*;
+
+mod span;
+
+mod store;
+pub use store::*;
+
 // std
 use std::{
 	collections::HashMap,
+	ops::AddAssign,
 	sync::Arc,
 	time::{Duration, Instant},
 };
 // crates.io
-use futures::{Stream, StreamExt};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use futures::{Stream, StreamExt, stream};
 use tokio::{
-	sync::mpsc::{self, Sender},
+	sync::{
+		Semaphore,
+		mpsc::{self, Sender},
+	},
 	time,
 };
-use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 // self
 use crate::{
 	_prelude::*,
-	http::{Auth, Client, Sse},
-	response::{Message, ResponseRequest, Role, StreamOptions},
+	api::{
+		ApiEventHandler,
+		chat::*,
+		embedding::{ApiEmbedding, EmbeddingRequest, estimate_tokens},
+	},
 	tool::*,
 };
 
@@ -26,22 +48,84 @@ use crate::{
 /// This agent implements the ReAct (Reasoning + Acting) pattern where the agent
 /// alternates between reasoning about the problem and taking actions using tools.
 #[derive(Clone)]
-pub struct Agent {
-	client: Client,
+pub struct Agent<A = Api> {
+	api: A,
 	options: AgentOptions,
 	custom_instructions: Option<String>,
-	tools: HashMap<String, Arc<dyn Tool>>,
+	tools: HashMap<String, Arc<dyn ToolT>>,
+	store: Option<Arc<dyn StateStore>>,
+	guardrails: Vec<Arc<dyn Guardrail>>,
+	vector_memory: Option<Arc<dyn VectorMemory>>,
+	hooks: AgentHooks,
+	tool_permits: Option<Arc<Semaphore>>,
 }
 impl Agent {
-	/// Create a new [`AgentBuilder`] with authentication.
-	pub fn builder(auth: Auth) -> AgentBuilder {
-		AgentBuilder { auth, options: Default::default(), custom_instructions: Default::default() }
+	/// Create a new [`AgentBuilder`].
+	pub fn builder() -> AgentBuilder {
+		AgentBuilder {
+			options: Default::default(),
+			custom_instructions: Default::default(),
+			hooks: Default::default(),
+		}
+	}
+}
+impl<A> Agent<A>
+where
+	A: 'static + Clone + ApiBase,
+{
+	/// Checkpoint the state into `store` every [`AgentOptions::checkpoint_interval`] steps and
+	/// once the run ends.
+	pub fn state_store<S>(&mut self, store: S)
+	where
+		S: 'static + StateStore,
+	{
+		self.store = Some(Arc::new(store));
+	}
+
+	/// Check the input, tool results and final answer of every run with `guardrail`.
+	pub fn guardrail<G>(&mut self, guardrail: G)
+	where
+		G: 'static + Guardrail,
+	{
+		self.guardrails.push(Arc::new(guardrail));
+	}
+
+	/// Remember tool results and answers in `memory` and recall the most relevant memories,
+	/// see [`AgentOptions::vector_memory`], into every prompt.
+	pub fn vector_memory<M>(&mut self, memory: M)
+	where
+		M: 'static + VectorMemory,
+	{
+		self.vector_memory = Some(Arc::new(memory));
+	}
+
+	/// Add `text` to the vector memory, e.g. a fact the agent should know.
+	pub async fn remember(&self, text: impl Into<String>) -> Result<()> {
+		let memory =
+			self.vector_memory.as_ref().ok_or_else(|| Error::any("no vector memory configured"))?;
+		let text = text.into();
+		let embedding = self.embed(&text).await?;
+
+		memory.insert(MemoryEntry { text, embedding }).await
+	}
+
+	/// Resume the run checkpointed under `id` in the state store, see [`Agent::resume`].
+	pub async fn resume_checkpoint(
+		&self,
+		id: &str,
+		cancellation: CancellationToken,
+	) -> Result<impl Stream<Item = AgentEvent>> {
+		let store = self.store.as_ref().ok_or_else(|| Error::any("no state store configured"))?;
+		let state =
+			store.load(id).await?.ok_or_else(|| Error::any(format!("no state saved for {id}")))?;
+
+		Ok(self.resume(state, cancellation).await)
 	}
 
 	/// Register a single tool with the agent.
 	pub fn register_tool<T>(&mut self, tool: T)
 	where
-		T: 'static + Tool,
+		T: 'static + ToolT,
 	{
 		let name = tool.name().to_string();
 
@@ -54,13 +138,13 @@ impl Agent {
 	pub fn register_tools<I, T>(&mut self, tools: I)
 	where
 		I: IntoIterator<Item = T>,
-		T: 'static + Tool,
+		T: 'static + ToolT,
 	{
 		tools.into_iter().for_each(|tool| self.register_tool(tool));
 	}
 
 	/// Find a registered tool by name.
-	pub fn find_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
+	pub fn find_tool(&self, name: &str) -> Option<Arc<dyn ToolT>> {
 		self.tools.get(name).cloned()
 	}
 
@@ -70,53 +154,99 @@ impl Agent {
 	}
 
 	/// Execute the ReAct loop with streaming output.
-	pub async fn react_stream(&self, state: AgentState) -> impl Stream<Item = AgentEvent> {
+	///
+	/// Cancelling `cancellation` aborts the in-flight completion or tool call and ends the stream
+	/// with [`AgentEvent::Completed`] reporting failure.
+	pub async fn react_stream(
+		&self,
+		state: AgentState,
+		cancellation: CancellationToken,
+	) -> impl Stream<Item = AgentEvent> {
+		self.spawn(state, false, cancellation)
+	}
+
+	/// Execute the ReAct loop and return its final answer as `T`, with the usage of the run.
+	///
+	/// Once the loop reaches a final answer, a strict JSON schema structured output call shapes
+	/// it into [`TypedAnswer::schema`], so the result is parsed rather than scraped from text.
+	/// The returned usage includes that call.
+	pub async fn run_typed<T>(
+		&self,
+		state: AgentState,
+		cancellation: CancellationToken,
+	) -> Result<(T, AgentUsage)>
+	where
+		T: TypedAnswer,
+	{
+		let (input, start) = (state.input.clone(), Instant::now());
+		let mut events = Box::pin(self.react_stream(state, cancellation.clone()).await);
+		let (mut answer, mut error, mut usage) = (None, None, AgentUsage::default());
+
+		while let Some(event) = events.next().await {
+			match event {
+				AgentEvent::FinalAnswer { content } => answer = Some(content),
+				AgentEvent::Error { message } => error = Some(message),
+				AgentEvent::Usage { total, .. } => usage = total,
+				_ => (),
+			}
+		}
+
+		let Some(answer) = answer else {
+			if cancellation.is_cancelled() {
+				Err(Error::Cancelled)?
+			}
+			if let Some(deadline) = self.options.deadline
+				&& start.elapsed() >= deadline
+			{
+				Err(AgentError::DeadlineExceeded(deadline))?
+			}
+
+			Err(AgentError::NoFinalAnswer(error))?
+		};
+
+		let (value, structured) = self.structured_answer(&input, &answer).await?;
+
+		usage += structured;
+
+		Ok((value, usage))
+	}
+
+	/// Continue the ReAct loop of a previously persisted `state`.
+	///
+	/// Past steps are not replayed, the stream starts with [`AgentEvent::Resumed`] and continues
+	/// with the next step. Cancelling `cancellation` ends it as in [`Agent::react_stream`].
+	pub async fn resume(
+		&self,
+		state: AgentState,
+		cancellation: CancellationToken,
+	) -> impl Stream<Item = AgentEvent> {
+		self.spawn(state, true, cancellation)
+	}
+
+	fn spawn(
+		&self,
+		state: AgentState,
+		resumed: bool,
+		cancellation: CancellationToken,
+	) -> impl Stream<Item = AgentEvent> {
 		let (tx, rx) = mpsc::channel(32);
 		// Spawn the agent execution in a separate task.
 		let agent = self.clone();
 
-		tokio::spawn(async move {
-			if let Err(e) = run_agent_stream(agent, state, tx.clone()).await {
-				let _ = tx.send(AgentEvent::err(e.to_string())).await;
-			}
-		});
+		tokio::spawn(run_agent_stream(agent, state, resumed, cancellation, tx));
 
-		ReceiverStream::new(rx)
+		stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|event| (event, rx)) })
 	}
 
 	/// Generate a reasoning stream with a pre-built prompt.
 	pub async fn reasoning_stream_with_prompt(
 		&self,
 		prompt: String,
-	) -> Result<impl Stream<Item = String> + '_> {
-		let system_message = Message { role: Role::Developer, content: self.system_prompt() };
-		let user_message = Message { role: Role::User, content: prompt };
-		let params = ResponseRequest {
-			messages: vec![system_message, user_message],
-			temperature: Some(self.options.temperature),
-			max_completion_tokens: Some(self.options.max_completion_tokens),
-			..Default::default()
-		};
-		let sse = self.completion_stream(params).await?;
-
-		Ok(Box::pin(sse.filter_map(|event| async move {
-			match event {
-				Ok(data) =>
-					if let Ok(json) = serde_json::from_str::<Value>(&data) {
-						json.get("choices")?
-							.get(0)?
-							.get("delta")?
-							.get("content")?
-							.as_str()
-							.map(|s| s.to_string())
-					} else {
-						None
-					},
-				Err(e) => {
-					tracing::warn!("error in reasoning stream: {e}");
-
-					None
-				},
+	) -> Result<impl Unpin + Stream<Item = String> + use<A>> {
+		Ok(Box::pin(self.completion_stream(prompt).await?.filter_map(|c| async move {
+			match c {
+				Completion::Token(token) => Some(token),
+				Completion::Usage(_) => None,
 			}
 		})))
 	}
@@ -125,36 +255,12 @@ impl Agent {
 	pub async fn reasoning_stream<'a>(
 		&'a self,
 		state: &'a AgentState,
-	) -> Result<impl Stream<Item = String> + 'a> {
+	) -> Result<impl Unpin + Stream<Item = String> + use<A>> {
 		let prompt = self.build_prompt(state);
-		let system_message = Message { role: Role::Developer, content: self.system_prompt() };
-		let user_message = Message { role: Role::User, content: prompt };
-		let params = ResponseRequest {
-			messages: vec![system_message, user_message],
-			temperature: Some(self.options.temperature),
-			max_completion_tokens: Some(self.options.max_completion_tokens),
-			..Default::default()
-		};
-		let sse = self.completion_stream(params).await?;
-
-		Ok(Box::pin(sse.filter_map(|event| async move {
-			match event {
-				Ok(data) =>
-					if let Ok(json) = serde_json::from_str::<Value>(&data) {
-						json.get("choices")?
-							.get(0)?
-							.get("delta")?
-							.get("content")?
-							.as_str()
-							.map(|s| s.to_string())
-					} else {
-						None
-					},
-				Err(e) => {
-					tracing::warn!("error in reasoning stream: {e}");
-
-					None
-				},
+		Ok(Box::pin(self.completion_stream(prompt).await?.filter_map(|c| async move {
+			match c {
+				Completion::Token(token) => Some(token),
+				Completion::Usage(_) => None,
 			}
 		})))
 	}
@@ -163,30 +269,27 @@ impl Agent {
 	fn build_prompt(&self, state: &AgentState) -> String {
 		let mut prompt = format!("Question: {}\n\n", state.input);
 
-		// Add conversation history.
-		for (i, step) in state.reasoning_steps.iter().enumerate() {
-			prompt.push_str(&format!("Thought {}: {step}\n", i + 1));
+		if let Some(summary) = &state.memory_summary {
+			prompt.push_str(&format!("Summary of earlier steps:\n{summary}\n\n"));
+		}
 
-			// Add corresponding tool call if it exists.
-			let Some(ToolCallResult { tool_call: ToolCall { name, args }, outcome }) =
-				state.tool_calls.get(i)
-			else {
-				continue;
-			};
+		// Add conversation history.
+		for i in state.summarized_steps..state.reasoning_steps.len() {
+			prompt.push_str(&format_step(state, i));
+		}
 
-			prompt.push_str(&format!("Action: {name}\n"));
-			prompt.push_str(&format!("Action Input: {args}\n"));
+		if let Some(AgentPlan { steps, current, .. }) = &state.plan {
+			prompt.push_str("Plan:\n");
 
-			// Format observation based on tool call outcome.
-			match &outcome {
-				ToolCallOutcome::Success { result } =>
-					prompt.push_str(&format!("Observation: {result}\n")),
-				ToolCallOutcome::Error { message } => {
-					prompt.push_str(&format!("Error: {message}\n"));
-				},
+			for (i, step) in steps.iter().enumerate() {
+				prompt.push_str(&format!("{}. {step}\n", i + 1));
 			}
 
-			prompt.push('\n');
+			match steps.get(*current) {
+				Some(step) =>
+					prompt.push_str(&format!("Current plan step {}: {step}\n\n", current + 1)),
+				None => prompt.push_str("All plan steps are done, give the Final Answer.\n\n"),
+			}
 		}
 
 		// Add next thought prompt.
@@ -248,32 +351,6 @@ Final Answer: [your complete answer to the original question]"#,
 		prompt
 	}
 
-	/// Parse tool call from LLM output using modern structured approach
-	fn parse_tool_call_structured(response: &Value) -> Option<ToolCall> {
-		// Modern approach: check for structured tool_calls in response
-		if let Some(tool_calls) = response
-			.get("choices")?
-			.get(0)?
-			.get("message")?
-			.get("tool_calls")
-			.and_then(|v| v.as_array())
-		{
-			if let Some(tool_call) = tool_calls.first() {
-				let function = tool_call.get("function")?;
-				let name = function.get("name")?.as_str()?.to_string();
-				let args_str = function.get("arguments")?.as_str()?;
-
-				// Parse arguments JSON
-				if let Ok(args) = serde_json::from_str::<Value>(args_str) {
-					tracing::debug!("Parsed structured tool call: {} with args: {}", name, args);
-					return Some(ToolCall { name, args });
-				}
-			}
-		}
-
-		None
-	}
-
 	/// Parse tool call from LLM output.
 	///
 	/// DEPRECATED: This method uses legacy text parsing and should be replaced
@@ -311,14 +388,13 @@ Final Answer: [your complete answer to the original question]"#,
 
 			tracing::debug!("Trying candidate {}: {}", i, candidate);
 
-			if let Ok(value) = serde_json::from_str::<Value>(candidate) {
-				if let (Some(tool), args) = (
+			if let Ok(value) = serde_json::from_str::<Value>(candidate)
+				&& let (Some(tool), args) = (
 					value.get("tool").and_then(|v| v.as_str()),
 					value.get("args").cloned().unwrap_or(Value::Null),
 				) {
-					tracing::debug!("Successfully parsed tool call: {} with args: {}", tool, args);
-					return Some(ToolCall { name: tool.to_string(), args });
-				}
+				tracing::debug!("Successfully parsed tool call: {} with args: {}", tool, args);
+				return Some(ToolCall { name: tool.to_string(), args });
 			}
 		}
 
@@ -333,22 +409,36 @@ Final Answer: [your complete answer to the original question]"#,
 		tx: &Sender<AgentEvent>,
 		tool_req: ToolCall,
 	) -> Result<ToolCallResult> {
-		time::timeout(self.options.timeout, self.call_tool(tx, tool_req.clone())).await.map_err(
-			|_| {
+		// Held until the call returns, the semaphore is never closed.
+		let (_permit, queue_time) = match &self.tool_permits {
+			Some(permits) => {
+				let start = Instant::now();
+				let permit = permits.acquire().await.expect("semaphore must be open; qed");
+
+				tracing::debug!("tool '{}' queued for {:?}", tool_req.name, start.elapsed());
+
+				(Some(permit), Some(start.elapsed()))
+			},
+			None => (None, None),
+		};
+
+		time::timeout(self.options.timeout, self.call_tool(tx, tool_req, queue_time))
+			.await
+			.map_err(|_| {
 				let e = Error::Timeout(self.options.timeout);
 
 				tracing::error!("{e}");
 
 				e
-			},
-		)?
+			})?
 	}
 
-	/// Execute a tool call
+	/// Execute a tool call and run the guardrails over its result
 	///
 	/// # Arguments
 	/// * `tx` - Channel sender for agent events
 	/// * `tool_req` - Tool call request
+	/// * `queue_time` - Time the call waited for a free slot, if tool calls are limited
 	///
 	/// # Returns
 	/// * `Result<ToolCallResult>` - Tool execution result
@@ -356,6 +446,7 @@ Final Answer: [your complete answer to the original question]"#,
 		&self,
 		tx: &Sender<AgentEvent>,
 		tool_req: ToolCall,
+		queue_time: Option<Duration>,
 	) -> Result<ToolCallResult> {
 		let ToolCall { name, args } = &tool_req;
 
@@ -375,23 +466,56 @@ Final Answer: [your complete answer to the original question]"#,
 		// Prefer streaming path if supported
 		if tool.supports_stream() {
 			tracing::debug!("Using streaming execution for tool '{}'", name);
-			match tool.call_stream(args.clone()).await {
+			match call_stream_validated(tool.as_ref(), args.clone()).await {
 				Ok(mut stream) => {
-					let mut acc = String::new();
+					let (mut acc, mut chunks, start) = (String::new(), 0, Instant::now());
+					// Chunks can't be guarded on their own, so the whole output is sent once
+					// guarded.
+					let guarded = !self.guardrails.is_empty();
 
 					while let Some(chunk) = stream.next().await {
+						if !guarded {
+							let _ = tx
+								.send(AgentEvent::ToolResult {
+									name: name.to_string(),
+									result: Value::String(chunk.clone()),
+									is_streaming: Some(true),
+									queue_time,
+									chunk: Some(chunks),
+								})
+								.await;
+						}
+
+						acc.push_str(&chunk);
+
+						chunks += 1;
+					}
+
+					tracing::debug!("tool '{name}' streaming completed");
+
+					if guarded {
+						let result = self.guard_tool_result(name, Value::String(acc), tx).await?;
 						let _ = tx
 							.send(AgentEvent::ToolResult {
 								name: name.to_string(),
-								result: Value::String(chunk.clone()),
-								is_streaming: Some(true),
+								result: result.clone(),
+								is_streaming: Some(false),
+								queue_time,
+								chunk: None,
 							})
 							.await;
 
-						acc.push_str(&chunk);
+						return Ok(ToolCallResult::success(name.to_string(), args.clone(), result));
 					}
 
-					tracing::debug!("tool '{name}' streaming completed");
+					let _ = tx
+						.send(AgentEvent::ToolResultDone {
+							name: name.to_string(),
+							chunks,
+							size: acc.len(),
+							duration: start.elapsed(),
+						})
+						.await;
 
 					return Ok(ToolCallResult::success(
 						name.to_string(),
@@ -399,6 +523,12 @@ Final Answer: [your complete answer to the original question]"#,
 						Value::String(acc),
 					));
 				},
+				// Invalid arguments fail the same way on the sync path.
+				Err(e @ Error::Tool(ToolError::SchemaViolation { .. })) => {
+					tracing::error!("{e}");
+
+					return Err(e);
+				},
 				Err(e) => {
 					tracing::error!("{e}");
 
@@ -410,14 +540,17 @@ Final Answer: [your complete answer to the original question]"#,
 
 		// Synchronous fallback
 		tracing::debug!("Using synchronous execution for tool '{}'", name);
-		match tool.call(args.clone()).await {
+		match call_validated(tool.as_ref(), args.clone()).await {
 			Ok(result) => {
 				tracing::debug!("Tool '{}' executed successfully", name);
+				let result = self.guard_tool_result(name, result, tx).await?;
 				let _ = tx
 					.send(AgentEvent::ToolResult {
 						name: name.to_string(),
 						result: result.clone(),
 						is_streaming: Some(false),
+						queue_time,
+						chunk: None,
 					})
 					.await;
 
@@ -430,36 +563,428 @@ Final Answer: [your complete answer to the original question]"#,
 		}
 	}
 
-	/// Send a completion request to the LLM
-	///
-	/// # Arguments
-	/// * `params` - Completion parameters
-	///
-	/// # Returns
-	/// * `Result<String>` - LLM response
-	async fn completion<P>(&self, params: P) -> Result<String>
+	/// Run the guardrails over `content`, returning the content to go on with.
+	async fn guard(
+		&self,
+		target: GuardrailTarget,
+		mut content: String,
+		tx: &Sender<AgentEvent>,
+	) -> Result<String> {
+		for guardrail in &self.guardrails {
+			let reason = match guardrail.check(&target, &content).await? {
+				GuardrailAction::Allow => continue,
+				GuardrailAction::Replace(replacement) => {
+					content = replacement;
+
+					None
+				},
+				GuardrailAction::Reject(reason) => Some(reason),
+			};
+
+			tracing::info!("guardrail triggered on {target}");
+
+			let _ = tx
+				.send(AgentEvent::GuardrailTriggered {
+					target: target.clone(),
+					rejected: reason.is_some(),
+					reason: reason.clone(),
+				})
+				.await;
+
+			if let Some(reason) = reason {
+				Err(AgentError::GuardrailRejected { target: target.to_string(), reason })?
+			}
+		}
+
+		Ok(content)
+	}
+
+	/// Temperature of auxiliary completions by `model`, pinned to the reasoning one once seeded.
+	fn pinned_temperature(&self, model: &Model) -> Option<f32> {
+		self.options.seed.and(self.options.profile(model).temperature)
+	}
+
+	/// Prices of the budget, otherwise those of the model.
+	fn pricing(&self) -> Option<Pricing> {
+		self.options
+			.budget
+			.as_ref()
+			.and_then(|b| b.pricing.clone())
+			.or_else(|| self.options.model.pricing())
+	}
+
+	/// Run the guardrails over the output of tool `name`, before any of it is sent as an event.
+	async fn guard_tool_result(
+		&self,
+		name: &str,
+		value: Value,
+		tx: &Sender<AgentEvent>,
+	) -> Result<Value> {
+		if self.guardrails.is_empty() {
+			return Ok(value);
+		}
+
+		let content = match &value {
+			Value::String(s) => s.clone(),
+			v => v.to_string(),
+		};
+		let target = GuardrailTarget::ToolResult { tool: name.to_owned() };
+		let guarded = self.guard(target, content.clone(), tx).await?;
+
+		Ok(if guarded != content { Value::String(guarded) } else { value })
+	}
+
+	/// Recall the memories most relevant to the input and the latest step.
+	async fn recall(&self, state: &AgentState) -> Vec<String> {
+		let Some(memory) = &self.vector_memory else { return Vec::new() };
+		let query = match state.reasoning_steps.last() {
+			Some(step) => format!("{}\n{step}", state.input),
+			None => state.input.clone(),
+		};
+		let hits = match self.embed(&query).await {
+			Ok(embedding) => memory.search(embedding, self.options.vector_memory.top_k).await,
+			Err(e) => Err(e),
+		};
+
+		match hits {
+			Ok(hits) => hits.into_iter().map(|h| h.text).collect(),
+			Err(e) => {
+				tracing::warn!("failed to recall memories: {e}");
+
+				Vec::new()
+			},
+		}
+	}
+
+	/// Remember `text` if a vector memory is configured, logging failures.
+	async fn try_remember(&self, text: String) {
+		if self.vector_memory.is_some()
+			&& let Err(e) = self.remember(text).await
+		{
+			tracing::warn!("failed to remember: {e}");
+		}
+	}
+
+	async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+		let resp = self
+			.api
+			.create_embedding(EmbeddingRequest {
+				input: Either::A(text.into()),
+				model: self.options.vector_memory.model.clone(),
+				..Default::default()
+			})
+			.await?;
+
+		resp.data
+			.into_iter()
+			.next()
+			.map(|o| o.embedding)
+			.ok_or_else(|| Error::any("empty embedding"))
+	}
+
+	/// Fold old steps into [`AgentState::memory_summary`] once the history outgrows
+	/// [`AgentOptions::memory`].
+	async fn compress_memory(&self, state: &mut AgentState, tx: &Sender<AgentEvent>) {
+		let Some(memory) = &self.options.memory else { return };
+		let recent = state.reasoning_steps.len().saturating_sub(memory.keep_recent);
+
+		if recent <= state.summarized_steps {
+			return;
+		}
+
+		let history = (state.summarized_steps..state.reasoning_steps.len())
+			.map(|i| format_step(state, i))
+			.collect::<String>();
+
+		if estimate_tokens(&history) <= memory.max_tokens {
+			return;
+		}
+
+		let old =
+			(state.summarized_steps..recent).map(|i| format_step(state, i)).collect::<String>();
+		let previous = state.memory_summary.as_deref().unwrap_or("(none)");
+		let request = ChatRequest {
+			messages: vec![
+				ChatMessage::Developer(ChatMessageCommon {
+					content: Either::A(format!(
+						"Condense the summary and the reasoning steps below into one short summary \
+						for the agent working on this question: {}\nKeep findings, tool results, \
+						failed attempts and open sub-goals, drop everything else.",
+						state.input
+					)),
+					name: None,
+				}),
+				ChatMessage::User(ChatMessageCommon {
+					content: Either::A(format!("Summary:\n{previous}\n\nSteps:\n{old}")),
+					name: None,
+				}),
+			],
+			model: memory.model.clone(),
+			seed: self.options.seed,
+			temperature: self.pinned_temperature(&memory.model),
+			..Default::default()
+		};
+		let chat = match self.api.create_chat(request).await {
+			Ok(chat) => chat,
+			Err(e) => {
+				tracing::warn!("failed to compress agent memory: {e}");
+
+				return;
+			},
+		};
+
+		state
+			.metadata
+			.record_extra_usage(&AgentUsage::from_chat(&chat.usage, self.pricing().as_ref()));
+
+		let Some(summary) = chat.choices.into_iter().next().and_then(|c| c.message.content) else {
+			return;
+		};
+
+		tracing::debug!("summarized steps {}..{recent}", state.summarized_steps);
+
+		let _ = tx
+			.send(AgentEvent::MemoryCompressed { from: state.summarized_steps, to: recent })
+			.await;
+
+		state.memory_summary = Some(summary);
+		state.summarized_steps = recent;
+	}
+
+	/// Shrink the result of a tool call to [`AgentOptions::tool_output`].
+	async fn limit_tool_output(&self, state: &mut AgentState, result: &mut ToolCallResult) {
+		let (Some(limit), ToolCallOutcome::Success { result: value }) =
+			(&self.options.tool_output, &mut result.outcome)
+		else {
+			return;
+		};
+		let content = match &*value {
+			Value::String(s) => s.clone(),
+			v => v.to_string(),
+		};
+
+		if content.chars().count() <= limit.max_chars {
+			return;
+		}
+
+		tracing::debug!(
+			"limiting output of tool '{}' to {} chars",
+			result.tool_call.name,
+			limit.max_chars
+		);
+
+		let limited = match &limit.strategy {
+			ToolOutputStrategy::Truncate => truncate_head(&content, limit.max_chars),
+			ToolOutputStrategy::HeadTail => truncate_head_tail(&content, limit.max_chars),
+			ToolOutputStrategy::Summarize { model } =>
+				match self.summarize(model, &state.input, &content, limit.max_chars).await {
+					Ok((summary, usage)) => {
+						state.metadata.record_extra_usage(&usage);
+
+						truncate_head(&summary, limit.max_chars)
+					},
+					Err(e) => {
+						tracing::warn!("failed to summarize tool output, truncating instead: {e}");
+
+						truncate_head_tail(&content, limit.max_chars)
+					},
+				},
+		};
+
+		*value = Value::String(limited);
+	}
+
+	/// Summarize `content` with `model`, keeping what matters for `input`.
+	async fn summarize(
+		&self,
+		model: &Model,
+		input: &str,
+		content: &str,
+		max_chars: usize,
+	) -> Result<(String, AgentUsage)> {
+		let request = ChatRequest {
+			messages: vec![
+				ChatMessage::Developer(ChatMessageCommon {
+					content: Either::A(format!(
+						"Summarize the tool output below in at most {max_chars} characters. Keep \
+						every fact, number and identifier relevant to the question: {input}"
+					)),
+					name: None,
+				}),
+				ChatMessage::User(ChatMessageCommon {
+					content: Either::A(content.into()),
+					name: None,
+				}),
+			],
+			model: model.clone(),
+			seed: self.options.seed,
+			temperature: self.pinned_temperature(model),
+			..Default::default()
+		};
+		let chat = self.api.create_chat(request).await?;
+		let usage = AgentUsage::from_chat(&chat.usage, self.pricing().as_ref());
+		let summary = chat
+			.choices
+			.into_iter()
+			.next()
+			.and_then(|c| c.message.content)
+			.ok_or_else(|| Error::any("empty summary"))?;
+
+		Ok((summary, usage))
+	}
+
+	/// Save `state` into the state store, if any.
+	async fn checkpoint(&self, state: &AgentState) {
+		let Some(store) = &self.store else { return };
+
+		if let Err(e) = store.save(state).await {
+			tracing::warn!("failed to checkpoint state {}: {e}", state.id);
+		}
+	}
+
+	/// Create a streaming completion for `prompt`, yielding the content tokens.
+	async fn completion_stream(
+		&self,
+		prompt: String,
+	) -> Result<impl Unpin + Stream<Item = Completion> + use<A>> {
+		let profile = self.options.profile(&self.options.model);
+		let request = ChatRequest {
+			messages: vec![
+				ChatMessage::Developer(ChatMessageCommon {
+					content: Either::A(self.system_prompt()),
+					name: None,
+				}),
+				ChatMessage::User(ChatMessageCommon { content: Either::A(prompt), name: None }),
+			],
+			model: self.options.model.clone(),
+			max_completion_tokens: Some(profile.max_completion_tokens),
+			reasoning_effort: profile.reasoning_effort,
+			seed: self.options.seed,
+			temperature: profile.temperature,
+			..Default::default()
+		};
+		let stream = self
+			.api
+			.create_chat_stream(request, SseOptions::new(ApiEventHandler::<ChatChunkObject>::new()))
+			.await?;
+
+		Ok(Box::pin(stream.filter_map(|chunk| async move {
+			match chunk {
+				Ok(ChatChunkObject { usage: Some(usage), .. }) => Some(Completion::Usage(usage)),
+				Ok(chunk) =>
+					chunk.choices.into_iter().next()?.delta?.content.map(Completion::Token),
+				Err(e) => {
+					tracing::warn!("error in reasoning stream: {e}");
+
+					None
+				},
+			}
+		})))
+	}
+
+	/// Reformat `answer` to the schema of `T` and parse it.
+	async fn structured_answer<T>(&self, input: &str, answer: &str) -> Result<(T, AgentUsage)>
 	where
-		P: Into<ResponseRequest>,
+		T: TypedAnswer,
 	{
-		self.client.post(params.into()).await
+		self.structured(
+			"Restate the final answer to the question in the required JSON format. \
+				Do not add information which is not in the answer."
+				.into(),
+			format!("Question: {input}\n\nFinal Answer: {answer}"),
+			T::name(),
+			T::schema(),
+		)
+		.await
 	}
 
-	/// Create a streaming completion request to the LLM
-	///
-	/// # Arguments
-	/// * `params` - Completion parameters
-	///
-	/// # Returns
-	/// * `Result<Sse>` - Server-sent events stream
-	async fn completion_stream<P>(&self, params: P) -> Result<Sse>
+	/// Ask the model for a plan of the remaining work on `state`.
+	async fn create_plan(&self, state: &mut AgentState) -> Result<Vec<String>> {
+		#[derive(Deserialize)]
+		struct Plan {
+			steps: Vec<String>,
+		}
+
+		let tools = self
+			.tools
+			.values()
+			.map(|t| format!("- {}: {}", t.name(), t.description()))
+			.collect::<Vec<_>>()
+			.join("\n");
+		let (Plan { steps }, usage) = self
+			.structured(
+				format!(
+					"Break the question down into a short list of concrete steps which lead to its \
+					answer. Each step should be doable with a single tool call or a single \
+					thought. If earlier work is shown, only plan what is left and work around \
+					the failures.\n\nAvailable tools:\n{tools}"
+				),
+				self.build_prompt(state),
+				"plan",
+				serde_json::json!({
+					"type": "object",
+					"properties": {
+						"steps": { "type": "array", "items": { "type": "string" } }
+					},
+					"required": ["steps"],
+					"additionalProperties": false
+				}),
+			)
+			.await?;
+
+		state.metadata.record_extra_usage(&usage);
+
+		Ok(steps)
+	}
+
+	/// Run a strict structured output completion and parse it into `T`.
+	async fn structured<T>(
+		&self,
+		instructions: String,
+		input: String,
+		name: &str,
+		schema: Value,
+	) -> Result<(T, AgentUsage)>
 	where
-		P: Into<ResponseRequest>,
+		T: DeserializeOwned,
 	{
-		let mut params = params.into();
-		params.stream = Some(true);
-		params.stream_options = Some(StreamOptions { include_usage: true });
+		let profile = self.options.profile(&self.options.model);
+		let request = ChatRequest {
+			messages: vec![
+				ChatMessage::Developer(ChatMessageCommon {
+					content: Either::A(instructions),
+					name: None,
+				}),
+				ChatMessage::User(ChatMessageCommon { content: Either::A(input), name: None }),
+			],
+			model: self.options.model.clone(),
+			max_completion_tokens: Some(profile.max_completion_tokens),
+			response_format: Some(ChatResponseFormat::JsonSchema {
+				json_schema: ChatResponseFormatJsonSchema {
+					name: name.into(),
+					description: None,
+					schema: Some(schema),
+					strict: Some(true),
+				},
+			}),
+			seed: self.options.seed,
+			temperature: self.pinned_temperature(&self.options.model),
+			..Default::default()
+		};
+		let chat = self.api.create_chat(request).await?;
+		let usage = AgentUsage::from_chat(&chat.usage, self.pricing().as_ref());
+		let message = chat
+			.choices
+			.into_iter()
+			.next()
+			.ok_or_else(|| Error::any("empty structured answer"))?
+			.message;
+
+		if let Some(refusal) = message.refusal {
+			Err(Error::any(format!("structured answer refused: {refusal}")))?
+		}
 
-		self.client.sse_post(params).await
+		Ok((serde_json::from_str(&message.content.unwrap_or_default())?, usage))
 	}
 
 	/// Validate that the agent's system prompt contains required ReAct format elements
@@ -530,50 +1055,109 @@ Final Answer: [your complete answer to the original question]"#,
 			})
 			.collect()
 	}
-
-	/// Enhanced completion with structured tool calling support
-	///
-	/// This method attempts to use modern function calling APIs when available,
-	/// falling back to text parsing for compatibility.
-	async fn completion_with_tools<P>(&self, params: P) -> Result<(String, Vec<ToolCall>)>
-	where
-		P: Into<ResponseRequest>,
-	{
-		let mut params = params.into();
-
-		// Add tools if available and supported
-		if !self.tools.is_empty() {
-			params.tools = Some(self.get_tool_definitions());
-			params.tool_choice = Some(serde_json::json!("auto"));
-		}
-
-		let response_text = self.client.post(params).await?;
-
-		// Try to parse as JSON response first (modern API)
-		if let Ok(response_json) = serde_json::from_str::<Value>(&response_text) {
-			if let Some(tool_call) = Self::parse_tool_call_structured(&response_json) {
-				return Ok((response_text, vec![tool_call]));
-			}
-		}
-
-		// Fallback to legacy text parsing
-		let tool_calls =
-			Self::parse_tool_call(&response_text).map(|tc| vec![tc]).unwrap_or_default();
-
-		Ok((response_text, tool_calls))
-	}
 }
 
 /// Builder for creating and configuring an Agent
 ///
 /// Provides a fluent interface for setting up an agent with custom options.
 pub struct AgentBuilder {
-	pub auth: Auth,
+	/// Options of the agent.
 	pub options: AgentOptions,
+	/// Instructions appended to the system prompt.
 	pub custom_instructions: Option<String>,
+	hooks: AgentHooks,
 }
 
 impl AgentBuilder {
+	/// Set the model used for reasoning.
+	pub fn model(mut self, model: Model) -> Self {
+		self.options.model = model;
+		self
+	}
+
+	/// Stop the run gracefully once `budget` is used up.
+	pub fn budget(mut self, budget: Budget) -> Self {
+		self.options.budget = Some(budget);
+		self
+	}
+
+	/// Limit the size of tool results.
+	pub fn tool_output(mut self, limit: ToolOutputLimit) -> Self {
+		self.options.tool_output = Some(limit);
+		self
+	}
+
+	/// Summarize old steps once the history grows too long.
+	pub fn memory(mut self, memory: MemoryOptions) -> Self {
+		self.options.memory = Some(memory);
+		self
+	}
+
+	/// Set how the vector memory embeds and recalls, see [`Agent::vector_memory`].
+	pub fn vector_memory(mut self, options: VectorMemoryOptions) -> Self {
+		self.options.vector_memory = options;
+		self
+	}
+
+	/// Set the execution strategy.
+	pub fn strategy(mut self, strategy: AgentStrategy) -> Self {
+		self.options.strategy = strategy;
+		self
+	}
+
+	/// Queue tool calls once `limit` of them are running, e.g. for tools behind a rate-limited
+	/// API.
+	pub fn max_concurrent_tools(mut self, limit: usize) -> Self {
+		self.options.max_concurrent_tools = Some(limit);
+		self
+	}
+
+	/// Checkpoint the state every `steps` steps, see [`Agent::state_store`].
+	pub fn checkpoint_interval(mut self, steps: usize) -> Self {
+		self.options.checkpoint_interval = steps;
+		self
+	}
+
+	/// Run `hook` before every reasoning step with the step index and the state so far.
+	pub fn on_step_start<F, Fut>(mut self, hook: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(usize, AgentState) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.hooks.on_step_start(hook);
+		self
+	}
+
+	/// Run `hook` with the result of every tool call, failed ones included.
+	pub fn on_tool_result<F, Fut>(mut self, hook: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(ToolCallResult) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.hooks.on_tool_result(hook);
+		self
+	}
+
+	/// Run `hook` with the final answer of a run, after the guardrails passed it.
+	pub fn on_final_answer<F, Fut>(mut self, hook: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(String) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.hooks.on_final_answer(hook);
+		self
+	}
+
+	/// Run `hook` with the message of every error a run reports through [`AgentEvent::Error`].
+	pub fn on_error<F, Fut>(mut self, hook: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(String) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.hooks.on_error(hook);
+		self
+	}
+
 	/// Set the maximum number of reasoning steps
 	///
 	/// # Arguments
@@ -598,6 +1182,12 @@ impl AgentBuilder {
 		self
 	}
 
+	/// Stop the run once it takes longer than `deadline`, see [`AgentOptions::deadline`].
+	pub fn deadline(mut self, deadline: Duration) -> Self {
+		self.options.deadline = Some(deadline);
+		self
+	}
+
 	/// Set the temperature for LLM responses
 	///
 	/// # Arguments
@@ -610,6 +1200,18 @@ impl AgentBuilder {
 		self
 	}
 
+	/// Pin the sampling of every completion to `seed` at temperature 0.
+	///
+	/// Models only sample deterministically on a best-effort basis. For byte-for-byte
+	/// reproducible runs, build the agent on a [`RecordingApi`] and replay its
+	/// [`RecordingApi::recording`] through a [`ReplayApi`], where the first request differing
+	/// from the recorded run fails.
+	pub fn deterministic(mut self, seed: u64) -> Self {
+		self.options.seed = Some(seed);
+		self.options.temperature = 0.;
+		self
+	}
+
 	/// Set the maximum completion tokens for LLM responses
 	///
 	/// # Arguments
@@ -652,16 +1254,27 @@ impl AgentBuilder {
 	/// Build the [`Agent`] instance with the configured options.
 	///
 	/// # Arguments
-	/// * `client` - HTTP client for API communication
+	/// * `api` - Client used to talk to the model, any [`ApiBase`] such as [`Api`]
 	///
 	/// # Returns
 	/// * `Agent` - Configured agent instance
-	pub fn build(self, client: Client) -> Agent {
+	pub fn build<A>(self, api: A) -> Agent<A>
+	where
+		A: 'static + Clone + ApiBase,
+	{
+		let tool_permits =
+			self.options.max_concurrent_tools.map(|n| Arc::new(Semaphore::new(n.max(1))));
+
 		Agent {
-			client,
+			api,
 			options: self.options,
 			tools: HashMap::new(),
 			custom_instructions: self.custom_instructions,
+			store: None,
+			guardrails: Vec::new(),
+			vector_memory: None,
+			hooks: self.hooks,
+			tool_permits,
 		}
 	}
 }
@@ -670,8 +1283,10 @@ impl AgentBuilder {
 ///
 /// Contains all the information about the agent's reasoning process,
 /// tool calls, and accumulated knowledge.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AgentState {
+	/// Identifier the state is checkpointed under.
+	pub id: String,
 	/// The original user input/question.
 	pub input: String,
 	/// Key-value storage for agent memory.
@@ -682,6 +1297,15 @@ pub struct AgentState {
 	pub tool_calls: Vec<ToolCallResult>,
 	/// Execution metadata and statistics.
 	pub metadata: AgentMetadata,
+	/// Plan of an [`AgentStrategy::PlanAndExecute`] run.
+	#[serde(default)]
+	pub plan: Option<AgentPlan>,
+	/// Summary of the first `summarized_steps` steps, which are left out of the prompt.
+	#[serde(default)]
+	pub memory_summary: Option<String>,
+	/// Number of steps covered by `memory_summary`.
+	#[serde(default)]
+	pub summarized_steps: usize,
 }
 
 impl AgentState {
@@ -695,11 +1319,15 @@ impl AgentState {
 	pub fn new(input: String) -> Self {
 		tracing::info!("Creating new agent state for input: {}", input);
 		Self {
+			id: uuid::Uuid::new_v4().to_string(),
 			input,
 			reasoning_steps: Vec::new(),
 			tool_calls: Vec::new(),
 			memory: HashMap::new(),
 			metadata: AgentMetadata::new(),
+			plan: None,
+			memory_summary: None,
+			summarized_steps: 0,
 		}
 	}
 
@@ -760,6 +1388,17 @@ impl AgentState {
 	}
 }
 
+/// Plan of an [`AgentStrategy::PlanAndExecute`] run.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AgentPlan {
+	/// Steps of the plan.
+	pub steps: Vec<String>,
+	/// Index of the step being worked on, `steps.len()` once all are done.
+	pub current: usize,
+	/// Number of times the plan was replaced after a failure.
+	pub replans: usize,
+}
+
 /// Metadata tracking agent execution statistics
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AgentMetadata {
@@ -775,6 +1414,12 @@ pub struct AgentMetadata {
 	pub end_time: Option<Instant>,
 	/// Total execution duration in milliseconds
 	pub duration_ms: Option<u64>,
+	/// Token usage accumulated over all calls, planning and summarizing included
+	#[serde(default)]
+	pub usage: AgentUsage,
+	/// Token usage of each reasoning step, indexed like the steps
+	#[serde(default)]
+	pub step_usage: Vec<AgentUsage>,
 }
 
 impl AgentMetadata {
@@ -793,6 +1438,17 @@ impl AgentMetadata {
 		}
 	}
 
+	/// Add the usage of a reasoning step
+	pub fn record_usage(&mut self, usage: AgentUsage) {
+		self.record_extra_usage(&usage);
+		self.step_usage.push(usage);
+	}
+
+	/// Add the usage of a call outside the reasoning steps, such as planning or summarizing
+	pub fn record_extra_usage(&mut self, usage: &AgentUsage) {
+		self.usage += usage.clone();
+	}
+
 	/// Get execution duration if available
 	pub fn get_duration(&self) -> Option<Duration> {
 		if let Some(ms) = self.duration_ms {
@@ -805,6 +1461,79 @@ impl AgentMetadata {
 	}
 }
 
+/// Token usage of an agent run or of a single step.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentUsage {
+	/// Prompt tokens.
+	pub prompt_tokens: u64,
+	/// Completion tokens, reasoning tokens included.
+	pub completion_tokens: u64,
+	/// Reasoning tokens.
+	pub reasoning_tokens: u64,
+	/// Spend, priced by [`Budget::pricing`] or else [`Model::pricing`], zero if neither is known.
+	pub cost: f64,
+}
+impl AgentUsage {
+	fn from_chat(usage: &ChatUsage, pricing: Option<&Pricing>) -> Self {
+		let (prompt_tokens, completion_tokens) =
+			(usage.prompt_tokens as u64, usage.completion_tokens as u64);
+
+		Self {
+			prompt_tokens,
+			completion_tokens,
+			reasoning_tokens: usage
+				.completion_tokens_details
+				.as_ref()
+				.map_or(0, |d| d.reasoning_tokens as u64),
+			cost: pricing.map_or(0., |p| Cost::from_chat(usage, p).total()),
+		}
+	}
+
+	/// Prompt and completion tokens combined.
+	pub fn total_tokens(&self) -> u64 {
+		self.prompt_tokens + self.completion_tokens
+	}
+}
+impl AddAssign for AgentUsage {
+	fn add_assign(&mut self, rhs: Self) {
+		self.prompt_tokens += rhs.prompt_tokens;
+		self.completion_tokens += rhs.completion_tokens;
+		self.reasoning_tokens += rhs.reasoning_tokens;
+		self.cost += rhs.cost;
+	}
+}
+
+/// What is left of [`AgentOptions::budget`], `None` for unlimited dimensions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentBudgetRemaining {
+	/// Prompt tokens left.
+	pub input_tokens: Option<u64>,
+	/// Completion tokens left.
+	pub output_tokens: Option<u64>,
+	/// Tokens left.
+	pub total_tokens: Option<u64>,
+	/// Spend left.
+	pub cost: Option<f64>,
+}
+impl AgentBudgetRemaining {
+	fn new(budget: &Budget, usage: &AgentUsage) -> Self {
+		Self {
+			input_tokens: budget.max_input_tokens.map(|m| m.saturating_sub(usage.prompt_tokens)),
+			output_tokens: budget
+				.max_output_tokens
+				.map(|m| m.saturating_sub(usage.completion_tokens)),
+			total_tokens: budget.max_total_tokens.map(|m| m.saturating_sub(usage.total_tokens())),
+			cost: budget.max_cost.map(|m| (m - usage.cost).max(0.)),
+		}
+	}
+
+	/// Whether any limit is used up.
+	pub fn is_exhausted(&self) -> bool {
+		[self.input_tokens, self.output_tokens, self.total_tokens].contains(&Some(0))
+			|| self.cost.is_some_and(|c| c <= 0.)
+	}
+}
+
 /// Events emitted during agent execution
 ///
 /// These events provide real-time updates about the agent's reasoning process,
@@ -814,47 +1543,171 @@ impl AgentMetadata {
 pub enum AgentEvent {
 	/// Individual reasoning token from the LLM
 	#[serde(rename = "reasoningToken")]
-	ReasoningToken { content: String },
+	ReasoningToken {
+		/// Text of the token.
+		content: String,
+	},
 
 	/// Complete reasoning step finished
 	#[serde(rename = "reasoningStepDone")]
-	ReasoningStepDone { content: String },
+	ReasoningStepDone {
+		/// Full text of the step.
+		content: String,
+	},
 
 	/// Tool call initiated
 	#[serde(rename = "toolCall")]
-	ToolCall { name: String, args: Value },
+	ToolCall {
+		/// Name of the called tool.
+		name: String,
+		/// Arguments passed to the tool.
+		args: Value,
+	},
 
 	/// Tool execution result (can be streamed)
 	#[serde(rename = "toolResult")]
 	ToolResult {
+		/// Name of the called tool.
 		name: String,
+		/// Output of the tool, or one chunk of it when streamed.
+		///
+		/// With guardrails registered, streamed outputs are sent whole once guarded.
 		result: Value,
+		/// Whether the result is one chunk of a streamed output.
 		#[serde(skip_serializing_if = "Option::is_none")]
 		is_streaming: Option<bool>,
+		/// Time the call waited for a free slot, see [`AgentOptions::max_concurrent_tools`].
+		#[serde(skip_serializing_if = "Option::is_none")]
+		queue_time: Option<Duration>,
+		/// Index of the chunk within a streamed result, starting at `0`.
+		#[serde(skip_serializing_if = "Option::is_none")]
+		chunk: Option<usize>,
+	},
+
+	/// A streamed tool result ended after `chunks` chunks of `size` bytes in total
+	#[serde(rename = "toolResultDone")]
+	ToolResultDone {
+		/// Name of the called tool.
+		name: String,
+		/// Number of streamed chunks.
+		chunks: usize,
+		/// Total size of the chunks in bytes.
+		size: usize,
+		/// Time spent streaming the chunks.
+		duration: Duration,
 	},
 
 	/// Agent's final answer to the question
 	#[serde(rename = "finalAnswer")]
-	FinalAnswer { content: String },
+	FinalAnswer {
+		/// Text of the answer.
+		content: String,
+	},
 
 	/// Error occurred during execution
 	#[serde(rename = "error")]
-	Error { message: String },
+	Error {
+		/// Description of the error.
+		message: String,
+	},
 
 	/// Execution metadata update
 	#[serde(rename = "metadata")]
 	Metadata {
+		/// Metadata of the run so far.
 		#[serde(flatten)]
 		data: AgentMetadata,
 	},
 
 	/// Agent started execution
 	#[serde(rename = "started")]
-	Started { max_steps: usize, tools: Vec<String> },
+	Started {
+		/// Maximum number of reasoning steps of the run.
+		max_steps: usize,
+		/// Names of the available tools.
+		tools: Vec<String>,
+	},
+
+	/// Token usage of a finished reasoning step
+	#[serde(rename = "usage")]
+	Usage {
+		/// Usage of the step.
+		step: AgentUsage,
+		/// Usage of the run so far.
+		total: AgentUsage,
+		/// What is left of the budget, if the run has one.
+		remaining: Option<AgentBudgetRemaining>,
+	},
+
+	/// The run stopped because its budget is used up
+	#[serde(rename = "budgetExceeded")]
+	BudgetExceeded {
+		/// Usage of the run.
+		usage: AgentUsage,
+		/// What is left of the budget, at least one limit is used up.
+		remaining: AgentBudgetRemaining,
+	},
+
+	/// A plan was created, `replanned` if it replaces one which failed
+	#[serde(rename = "planCreated")]
+	PlanCreated {
+		/// Steps of the plan in order.
+		steps: Vec<String>,
+		/// Whether the plan replaces a failed one.
+		replanned: bool,
+	},
+
+	/// Work on the plan step at `index` started
+	#[serde(rename = "planStepStarted")]
+	PlanStepStarted {
+		/// Position of the step within the plan.
+		index: usize,
+		/// Description of the step.
+		step: String,
+	},
+
+	/// Steps `from..to` were folded into the memory summary
+	#[serde(rename = "memoryCompressed")]
+	MemoryCompressed {
+		/// First folded step.
+		from: usize,
+		/// End of the folded steps, exclusive.
+		to: usize,
+	},
+
+	/// A guardrail replaced or rejected content
+	#[serde(rename = "guardrailTriggered")]
+	GuardrailTriggered {
+		/// Content the guardrail checked.
+		target: GuardrailTarget,
+		/// Whether the content was rejected rather than replaced.
+		rejected: bool,
+		/// Reason given by the guardrail.
+		reason: Option<String>,
+	},
+
+	/// Agent resumed a persisted run at `step`
+	#[serde(rename = "resumed")]
+	Resumed {
+		/// ID of the resumed run.
+		id: String,
+		/// Step the run continues at.
+		step: usize,
+	},
 
-	/// Agent completed execution
+	/// Agent completed execution, with the best answer so far if it ran out of time
 	#[serde(rename = "completed")]
-	Completed { success: bool, total_steps: usize, duration: Option<Duration> },
+	Completed {
+		/// Whether the run produced a final answer.
+		success: bool,
+		/// Number of reasoning steps taken.
+		total_steps: usize,
+		/// Wall-clock time of the run.
+		duration: Option<Duration>,
+		/// Best answer so far of a run which ran out of time.
+		#[serde(skip_serializing_if = "Option::is_none")]
+		partial_answer: Option<String>,
+	},
 }
 
 impl AgentEvent {
@@ -875,32 +1728,170 @@ impl AgentEvent {
 
 	/// Create a Completed event
 	pub fn completed(success: bool, total_steps: usize, duration: Option<Duration>) -> Self {
-		Self::Completed { success, total_steps, duration }
+		Self::Completed { success, total_steps, duration, partial_answer: None }
 	}
 }
 
 /// Options for configuring the agent's behavior.
 #[derive(Clone, Debug)]
 pub struct AgentOptions {
+	/// Model used for reasoning.
+	pub model: Model,
+	/// Maximum number of reasoning steps.
 	pub max_steps: usize,
+	/// Timeout of a single tool call.
 	pub timeout: Duration,
+	/// Wall-clock limit of a whole run, measured from its start or resumption.
+	pub deadline: Option<Duration>,
+	/// Sampling temperature.
 	pub temperature: f32,
+	/// Maximum number of tokens per reasoning step.
 	pub max_completion_tokens: u32,
+	/// Whether to request reasoning effort from compatible models.
 	pub reasoning_effort: bool,
+	/// Number of steps between two checkpoints, `0` only checkpoints at the end of a run.
+	pub checkpoint_interval: usize,
+	/// Token and spend limits of a run.
+	pub budget: Option<Budget>,
+	/// How the agent works towards the answer.
+	pub strategy: AgentStrategy,
+	/// Size limit of a tool result, applied before it enters the prompt.
+	pub tool_output: Option<ToolOutputLimit>,
+	/// Summarization of old steps once the history grows too long.
+	pub memory: Option<MemoryOptions>,
+	/// Embedding and recall of the vector memory, see [`Agent::vector_memory`].
+	pub vector_memory: VectorMemoryOptions,
+	/// Maximum number of tool calls running at once, shared by all runs of the agent and its
+	/// clones, `None` for no limit.
+	pub max_concurrent_tools: Option<usize>,
+	/// Seed of every completion, see [`AgentBuilder::deterministic`].
+	pub seed: Option<u64>,
 }
 
+impl AgentOptions {
+	/// Map the options onto what `model` supports.
+	///
+	/// The temperature is only sent to models which accept one, the reasoning effort only to
+	/// reasoning models. The completion tokens are capped at the output limit of `model`.
+	pub fn profile(&self, model: &Model) -> ModelProfile {
+		ModelProfile {
+			temperature: model.temperature().then_some(self.temperature),
+			reasoning_effort: (model.reasoning() && self.reasoning_effort)
+				.then_some(ReasoningEffort::Medium),
+			max_completion_tokens: model
+				.max_output_tokens()
+				.map_or(self.max_completion_tokens, |max| max.min(self.max_completion_tokens)),
+		}
+	}
+}
 impl Default for AgentOptions {
 	fn default() -> Self {
 		Self {
+			model: Default::default(),
 			max_steps: 10,
 			timeout: Duration::from_secs(300),
+			deadline: None,
 			temperature: 0.7,
 			max_completion_tokens: 4000,
 			reasoning_effort: false,
+			checkpoint_interval: 1,
+			budget: None,
+			strategy: Default::default(),
+			tool_output: None,
+			memory: None,
+			vector_memory: Default::default(),
+			max_concurrent_tools: None,
+			seed: None,
 		}
 	}
 }
 
+/// Request options fitted to a model, see [`AgentOptions::profile`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelProfile {
+	/// Sampling temperature, `None` if the model does not take one.
+	pub temperature: Option<f32>,
+	/// Reasoning effort, `None` if disabled or not supported by the model.
+	pub reasoning_effort: Option<ReasoningEffort>,
+	/// Maximum number of tokens per completion.
+	pub max_completion_tokens: u32,
+}
+
+/// Sliding window over the step history, see [`AgentOptions::memory`].
+///
+/// Once the history in the prompt exceeds `max_tokens`, all but the `keep_recent` latest steps
+/// are summarized by `model` and replaced with the summary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryOptions {
+	/// History size, as estimated by [`estimate_tokens`], which triggers a summary.
+	pub max_tokens: usize,
+	/// Number of latest steps always kept verbatim.
+	pub keep_recent: usize,
+	/// Model writing the summary, ideally a cheap one.
+	pub model: Model,
+}
+
+/// Size limit of tool results, see [`AgentOptions::tool_output`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolOutputLimit {
+	/// Maximum number of characters.
+	pub max_chars: usize,
+	/// How results over the limit are shrunk.
+	pub strategy: ToolOutputStrategy,
+}
+
+/// How a [`ToolOutputLimit`] shrinks a result.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ToolOutputStrategy {
+	/// Keep the beginning.
+	Truncate,
+	/// Keep the beginning and the end, which often holds errors or totals.
+	#[default]
+	HeadTail,
+	/// Let `model`, ideally a cheap one, summarize the result.
+	///
+	/// Falls back to [`ToolOutputStrategy::HeadTail`] if the summary fails.
+	Summarize {
+		/// Model writing the summary.
+		model: Model,
+	},
+}
+
+/// Execution strategy of an [`Agent`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AgentStrategy {
+	/// Interleave reasoning and tool calls until the answer is found.
+	#[default]
+	React,
+	/// Plan the steps upfront and work through them one by one, re-planning at most
+	/// `max_replans` times after a failed tool call, then answer.
+	PlanAndExecute {
+		/// Maximum number of re-plans.
+		max_replans: usize,
+	},
+}
+
+/// Final answer type of [`Agent::run_typed`].
+pub trait TypedAnswer
+where
+	Self: DeserializeOwned,
+{
+	/// Name of the response format, `^[a-zA-Z0-9_-]+$`.
+	fn name() -> &'static str;
+
+	/// JSON schema describing `Self`.
+	///
+	/// Strict structured outputs require every property to be listed in `required` and
+	/// `additionalProperties` to be `false`.
+	fn schema() -> Value;
+}
+
+/// Item of a reasoning completion stream.
+enum Completion {
+	Token(String),
+	Usage(ChatUsage),
+}
+
 /// Main agent execution loop with streaming updates
 ///
 /// This function implements the ReAct pattern by alternating between reasoning
@@ -909,171 +1900,565 @@ impl Default for AgentOptions {
 /// # Arguments
 /// * `agent` - The agent instance
 /// * `mut state` - Mutable agent state
+/// * `resumed` - Whether `state` comes from a previous run
+/// * `cancellation` - Aborts the run once cancelled
 /// * `tx` - Channel sender for streaming events
-///
-/// # Returns
-/// * `Result<()>` - Success or error
-async fn run_agent_stream(
-	agent: Agent,
+async fn run_agent_stream<A>(
+	agent: Agent<A>,
 	mut state: AgentState,
+	resumed: bool,
+	cancellation: CancellationToken,
 	tx: Sender<AgentEvent>,
-) -> Result<()> {
-	tracing::info!("Starting agent execution for input: {}", state.input);
+) where
+	A: 'static + Clone + ApiBase,
+{
+	let steps = cancellation.run_until_cancelled(run_steps(&agent, &mut state, resumed, &tx));
+	// Dropping the steps future aborts whatever completion or tool call is in flight.
+	let res = match agent.options.deadline {
+		Some(deadline) => match time::timeout(deadline, steps).await {
+			Ok(res) => res,
+			Err(_) => {
+				tracing::warn!(
+					"agent execution {} exceeded its deadline of {deadline:?}",
+					state.id
+				);
+
+				Some(Err(AgentError::DeadlineExceeded(deadline).into()))
+			},
+		},
+		None => steps.await,
+	};
+
+	match res {
+		Some(Ok(())) => (),
+		Some(Err(e)) => fail(&agent, &mut state, e, &tx).await,
+		None => {
+			tracing::info!("agent execution {} cancelled", state.id);
+
+			state.metadata.complete();
+
+			let _ = tx
+				.send(AgentEvent::completed(
+					false,
+					state.total_steps(),
+					state.metadata.get_duration(),
+				))
+				.await;
+		},
+	}
 
-	// Send startup event
-	let _ = tx.send(AgentEvent::started(agent.options.max_steps, agent.list_tools())).await;
+	agent.checkpoint(&state).await;
+}
 
-	for step in 0..agent.options.max_steps {
+/// End a failed run, reporting `e` before completing it.
+async fn fail<A>(agent: &Agent<A>, state: &mut AgentState, e: Error, tx: &Sender<AgentEvent>)
+where
+	A: 'static + Clone + ApiBase,
+{
+	let message = e.to_string();
+
+	agent.hooks.error(&message).await;
+
+	let _ = tx.send(AgentEvent::err(message)).await;
+
+	state.metadata.complete();
+
+	// Without a final answer, the latest thought is the closest thing to one.
+	let partial_answer = match e {
+		Error::Agent(AgentError::DeadlineExceeded(_)) => state
+			.reasoning_steps
+			.last()
+			.map(|s| extract_final_answer(s).unwrap_or_else(|| s.clone())),
+		_ => None,
+	};
+	let _ = tx
+		.send(AgentEvent::Completed {
+			success: false,
+			total_steps: state.total_steps(),
+			duration: state.metadata.get_duration(),
+			partial_answer,
+		})
+		.await;
+}
+
+async fn run_steps<A>(
+	agent: &Agent<A>,
+	state: &mut AgentState,
+	resumed: bool,
+	tx: &Sender<AgentEvent>,
+) -> Result<()>
+where
+	A: 'static + Clone + ApiBase,
+{
+	if resumed {
+		tracing::info!("Resuming agent execution {} at step {}", state.id, state.current_step());
+
+		// Instants are not persisted, measure the duration from the resumption on.
+		state.metadata.start_time.get_or_insert_with(Instant::now);
+
+		let _ =
+			tx.send(AgentEvent::Resumed { id: state.id.clone(), step: state.current_step() }).await;
+	} else {
+		tracing::info!("Starting agent execution for input: {}", state.input);
+
+		// Send startup event
+		let _ = tx.send(AgentEvent::started(agent.options.max_steps, agent.list_tools())).await;
+
+		let input = std::mem::take(&mut state.input);
+
+		state.input = agent
+			.guard(GuardrailTarget::Input, input.clone(), tx)
+			.await
+			.inspect_err(|_| state.input = input)?;
+	}
+
+	if let AgentStrategy::PlanAndExecute { max_replans } = agent.options.strategy
+		&& run_plan(agent, state, max_replans, tx).await?
+	{
+		return Ok(());
+	}
+
+	for step in state.current_step()..agent.options.max_steps {
 		tracing::debug!("Starting step {} of {}", step + 1, agent.options.max_steps);
 
 		// Check if we already have a final answer.
-		if state.is_complete() {
-			if let Some(answer) = extract_final_answer(state.reasoning_steps.last().unwrap()) {
-				tracing::info!("Agent found final answer: {}", answer);
-				let _ = tx.send(AgentEvent::FinalAnswer { content: answer }).await;
-				let _ = tx
-					.send(AgentEvent::completed(
-						true,
-						state.total_steps(),
-						state.metadata.get_duration(),
-					))
-					.await;
-				return Ok(());
-			}
+		if state.is_complete()
+			&& let Some(answer) = extract_final_answer(state.reasoning_steps.last().unwrap())
+		{
+			tracing::info!("Agent found final answer: {}", answer);
+			let answer = agent.guard(GuardrailTarget::FinalAnswer, answer, tx).await?;
+
+			agent.hooks.final_answer(&answer).await;
+
+			let _ = tx.send(AgentEvent::FinalAnswer { content: answer }).await;
+			let _ = tx
+				.send(AgentEvent::completed(
+					true,
+					state.total_steps(),
+					state.metadata.get_duration(),
+				))
+				.await;
+			return Ok(());
 		}
 
-		// Build prompt first to avoid borrow conflicts.
-		let prompt = agent.build_prompt(&state);
-		// Generate reasoning using prompt (no state reference needed).
-		let reasoning_res = agent.reasoning_stream_with_prompt(prompt).await;
+		match run_step(agent, state, step, tx).await? {
+			StepOutcome::Empty => continue,
+			StepOutcome::FinalAnswer => return Ok(()),
+			StepOutcome::Continued { .. } => (),
+		}
 
-		match reasoning_res {
-			Ok(mut stream) => {
-				let mut full_reasoning = String::new();
-				let mut token_buffer = Vec::new();
+		if finish_step(agent, state, step, tx).await {
+			return Ok(());
+		}
+	}
 
-				// Collect reasoning tokens
-				while let Some(token) = stream.next().await {
-					token_buffer.push(token.clone());
-					full_reasoning.push_str(&token);
+	// Max steps reached.
+	tracing::warn!(
+		"Agent reached maximum steps ({}) without final answer",
+		agent.options.max_steps
+	);
 
-					// Batch send tokens for better performance
-					if token_buffer.len() >= 5 {
-						for chunk in token_buffer.drain(..) {
-							let _ = tx.send(AgentEvent::reasoning_token(chunk)).await;
-						}
-					}
-				}
+	state.metadata.complete();
 
-				// Send remaining tokens
-				for chunk in token_buffer {
-					let _ = tx.send(AgentEvent::reasoning_token(chunk)).await;
-				}
+	// TODO raise error.
+	let e = AgentError::MaxStepsExceeded(agent.options.max_steps).to_string();
 
-				if full_reasoning.trim().is_empty() {
-					tracing::warn!("Empty reasoning generated at step {}", step + 1);
-					continue;
-				}
+	agent.hooks.error(&e).await;
 
-				tracing::debug!("generated reasoning: {full_reasoning}");
+	let _ = tx.send(AgentEvent::err(e)).await;
+	let _ = tx
+		.send(AgentEvent::completed(false, state.total_steps(), state.metadata.get_duration()))
+		.await;
 
-				let _ = tx
-					.send(AgentEvent::ReasoningStepDone { content: full_reasoning.clone() })
-					.await;
+	Ok(())
+}
 
-				state.add_step(full_reasoning.clone());
+/// Plan phase of [`AgentStrategy::PlanAndExecute`].
+///
+/// Works through the plan one step at a time and re-plans after a failed tool call. Returns
+/// whether the run ended, otherwise the ReAct loop takes over to give the final answer.
+async fn run_plan<A>(
+	agent: &Agent<A>,
+	state: &mut AgentState,
+	max_replans: usize,
+	tx: &Sender<AgentEvent>,
+) -> Result<bool>
+where
+	A: 'static + Clone + ApiBase,
+{
+	if state.plan.is_none() {
+		let steps = agent.create_plan(state).await?;
+
+		tracing::info!("created plan with {} steps", steps.len());
+
+		let _ = tx.send(AgentEvent::PlanCreated { steps: steps.clone(), replanned: false }).await;
+
+		state.plan = Some(AgentPlan { steps, current: 0, replans: 0 });
+	}
 
-				// Check for final answer in the reasoning
-				if full_reasoning.to_lowercase().contains("final answer:") {
-					if let Some(answer) = extract_final_answer(&full_reasoning) {
-						tracing::info!("Agent provided final answer: {}", answer);
-						let _ = tx.send(AgentEvent::FinalAnswer { content: answer }).await;
+	for step in state.current_step()..agent.options.max_steps {
+		let Some(plan) = &state.plan else { break };
+		let Some(task) = plan.steps.get(plan.current) else { break };
+		let _ =
+			tx.send(AgentEvent::PlanStepStarted { index: plan.current, step: task.clone() }).await;
 
-						state.metadata.complete();
-						let _ = tx
-							.send(AgentEvent::completed(
-								true,
-								state.total_steps(),
-								state.metadata.get_duration(),
-							))
-							.await;
-						return Ok(());
-					}
-				}
+		match run_step(agent, state, step, tx).await? {
+			StepOutcome::Empty => continue,
+			StepOutcome::FinalAnswer => return Ok(true),
+			StepOutcome::Continued { tool_failed } => {
+				let replans = state.plan.as_ref().map_or(0, |p| p.replans);
 
-				// Parse for tool call
-				if let Some(tool_req) = Agent::parse_tool_call(&full_reasoning) {
-					tracing::info!(
-						"Parsed tool call: {} with args: {}",
-						tool_req.name,
-						tool_req.args
-					);
+				if tool_failed && replans < max_replans {
+					let steps = agent.create_plan(state).await?;
+
+					tracing::info!("re-planned with {} steps", steps.len());
 
 					let _ = tx
-						.send(AgentEvent::ToolCall {
-							name: tool_req.name.clone(),
-							args: tool_req.args.clone(),
-						})
+						.send(AgentEvent::PlanCreated { steps: steps.clone(), replanned: true })
 						.await;
 
-					match agent.call_tool_with_timeout(&tx, tool_req.clone()).await {
-						Ok(result) => {
-							tracing::info!("tool call successful: {}", result.tool_call.name);
-
-							state.add_tool_call(result);
-						},
-						Err(e) => {
-							let _ = tx.send(AgentEvent::err(e.to_string())).await;
-
-							// Add error as observation for the agent to learn from
-							state.add_tool_call(ToolCallResult::err(
-								tool_req.name.clone(),
-								tool_req.args.clone(),
-								e.to_string(),
-							));
-						},
-					}
+					state.plan = Some(AgentPlan { steps, current: 0, replans: replans + 1 });
+				} else if let Some(plan) = &mut state.plan {
+					plan.current += 1;
 				}
 			},
-			Err(e) => {
-				tracing::error!("reasoning failed at step {}: {e}", step + 1);
+		}
+
+		if finish_step(agent, state, step, tx).await {
+			return Ok(true);
+		}
+	}
 
-				let _ = tx.send(AgentEvent::err(e.to_string())).await;
+	Ok(false)
+}
 
-				state.metadata.complete();
+/// Result of a single reasoning step.
+enum StepOutcome {
+	/// The model produced no reasoning.
+	Empty,
+	/// The model gave its final answer and the run is completed.
+	FinalAnswer,
+	/// The run goes on.
+	Continued { tool_failed: bool },
+}
+
+/// Reason about the current state and run the requested tool, if any.
+async fn run_step<A>(
+	agent: &Agent<A>,
+	state: &mut AgentState,
+	step: usize,
+	tx: &Sender<AgentEvent>,
+) -> Result<StepOutcome>
+where
+	A: 'static + Clone + ApiBase,
+{
+	agent.hooks.step_start(step, state).await;
+
+	let span = span::step(step);
+	let (start, before) = (Instant::now(), state.metadata.usage.clone());
+	let res = run_step_inner(agent, state, step, tx).instrument(span.clone()).await;
+	let after = &state.metadata.usage;
+
+	// Summaries and the like count towards the step, too.
+	span::record_usage(
+		&span,
+		&AgentUsage {
+			prompt_tokens: after.prompt_tokens - before.prompt_tokens,
+			completion_tokens: after.completion_tokens - before.completion_tokens,
+			reasoning_tokens: after.reasoning_tokens - before.reasoning_tokens,
+			cost: after.cost - before.cost,
+		},
+	);
+	span::record_duration(&span, start);
+
+	res
+}
+
+async fn run_step_inner<A>(
+	agent: &Agent<A>,
+	state: &mut AgentState,
+	step: usize,
+	tx: &Sender<AgentEvent>,
+) -> Result<StepOutcome>
+where
+	A: 'static + Clone + ApiBase,
+{
+	agent.compress_memory(state, tx).await;
+
+	// Build prompt first to avoid borrow conflicts.
+	let mut prompt = agent.build_prompt(state);
+	let recalled = agent.recall(state).await;
+
+	if !recalled.is_empty() {
+		prompt = format!("Relevant memories:\n- {}\n\n{prompt}", recalled.join("\n- "));
+	}
+
+	// Generate reasoning using prompt (no state reference needed).
+	let (llm_span, start) = (span::llm_call(&agent.options.model), Instant::now());
+	let reasoning_res = reason(agent, prompt, tx).instrument(llm_span.clone()).await;
+
+	if let Ok((_, usage)) = &reasoning_res {
+		span::record_usage(&llm_span, usage);
+	}
+
+	span::record_duration(&llm_span, start);
+
+	match reasoning_res {
+		Ok((full_reasoning, usage)) => {
+			state.metadata.record_usage(usage.clone());
+
+			let remaining = agent
+				.options
+				.budget
+				.as_ref()
+				.map(|b| AgentBudgetRemaining::new(b, &state.metadata.usage));
+			let _ = tx
+				.send(AgentEvent::Usage {
+					step: usage,
+					total: state.metadata.usage.clone(),
+					remaining: remaining.clone(),
+				})
+				.await;
+
+			if full_reasoning.trim().is_empty() {
+				tracing::warn!("Empty reasoning generated at step {}", step + 1);
+				return Ok(StepOutcome::Empty);
+			}
+
+			tracing::debug!("generated reasoning: {full_reasoning}");
 
+			let _ =
+				tx.send(AgentEvent::ReasoningStepDone { content: full_reasoning.clone() }).await;
+
+			state.add_step(full_reasoning.clone());
+
+			// Check for final answer in the reasoning
+			if full_reasoning.to_lowercase().contains("final answer:")
+				&& let Some(answer) = extract_final_answer(&full_reasoning)
+			{
+				tracing::info!("Agent provided final answer: {}", answer);
+				let answer = agent.guard(GuardrailTarget::FinalAnswer, answer, tx).await?;
+
+				agent.try_remember(format!("Question: {}\nAnswer: {answer}", state.input)).await;
+				agent.hooks.final_answer(&answer).await;
+
+				let _ = tx.send(AgentEvent::FinalAnswer { content: answer }).await;
+
+				state.metadata.complete();
 				let _ = tx
 					.send(AgentEvent::completed(
-						false,
+						true,
 						state.total_steps(),
 						state.metadata.get_duration(),
 					))
 					.await;
+				return Ok(StepOutcome::FinalAnswer);
+			}
+
+			let mut tool_failed = false;
+
+			// Parse for tool call
+			if let Some(tool_req) = Agent::<A>::parse_tool_call(&full_reasoning) {
+				tracing::info!("Parsed tool call: {} with args: {}", tool_req.name, tool_req.args);
+
+				let _ = tx
+					.send(AgentEvent::ToolCall {
+						name: tool_req.name.clone(),
+						args: tool_req.args.clone(),
+					})
+					.await;
+
+				let (tool_span, start) = (span::tool_call(&tool_req.name), Instant::now());
+				let tool_res = agent
+					.call_tool_with_timeout(tx, tool_req.clone())
+					.instrument(tool_span.clone())
+					.await;
+
+				tool_span.record("success", tool_res.is_ok());
+				span::record_duration(&tool_span, start);
+
+				match tool_res {
+					Ok(mut result) => {
+						tracing::info!("tool call successful: {}", result.tool_call.name);
+
+						agent.limit_tool_output(state, &mut result).await;
+
+						if let ToolCallOutcome::Success { result: value } = &result.outcome {
+							let ToolCall { name, args } = &result.tool_call;
+
+							agent.try_remember(format!("{name}({args}) returned: {value}")).await;
+						}
+
+						agent.hooks.tool_result(&result).await;
+						state.add_tool_call(result);
+					},
+					Err(e) => {
+						let message = e.to_string();
+
+						agent.hooks.error(&message).await;
+
+						let _ = tx.send(AgentEvent::err(message.clone())).await;
+						let result = ToolCallResult::err(
+							tool_req.name.clone(),
+							tool_req.args.clone(),
+							message,
+						);
+
+						agent.hooks.tool_result(&result).await;
+						// Add error as observation for the agent to learn from
+						state.add_tool_call(result);
+
+						tool_failed = true;
+					},
+				}
+			}
+
+			Ok(StepOutcome::Continued { tool_failed })
+		},
+		Err(e) => {
+			tracing::error!("reasoning failed at step {}: {e}", step + 1);
+
+			// The run reports the error once it ends.
+			Err(e)
+		},
+	}
+}
+
+/// Stream a completion of `prompt`, forwarding its tokens.
+async fn reason<A>(
+	agent: &Agent<A>,
+	prompt: String,
+	tx: &Sender<AgentEvent>,
+) -> Result<(String, AgentUsage)>
+where
+	A: 'static + Clone + ApiBase,
+{
+	let mut stream = agent.completion_stream(prompt).await?;
+	let mut full_reasoning = String::new();
+	let mut token_buffer = Vec::new();
+	let mut usage = AgentUsage::default();
+
+	// Collect reasoning tokens
+	while let Some(completion) = stream.next().await {
+		let token = match completion {
+			Completion::Token(token) => token,
+			Completion::Usage(u) => {
+				usage = AgentUsage::from_chat(&u, agent.pricing().as_ref());
 
-				return Err(e);
+				continue;
 			},
+		};
+
+		token_buffer.push(token.clone());
+		full_reasoning.push_str(&token);
+
+		// Batch send tokens for better performance
+		if token_buffer.len() >= 5 {
+			for chunk in token_buffer.drain(..) {
+				let _ = tx.send(AgentEvent::reasoning_token(chunk)).await;
+			}
 		}
+	}
 
-		// Send metadata update
-		let _ = tx.send(AgentEvent::Metadata { data: state.metadata.clone() }).await;
+	// Send remaining tokens
+	for chunk in token_buffer {
+		let _ = tx.send(AgentEvent::reasoning_token(chunk)).await;
 	}
 
-	// Max steps reached.
-	tracing::warn!(
-		"Agent reached maximum steps ({}) without final answer",
-		agent.options.max_steps
-	);
+	Ok((full_reasoning, usage))
+}
 
-	state.metadata.complete();
+/// Report metadata, enforce the budget and checkpoint after `step`.
+///
+/// Returns whether the run ended.
+async fn finish_step<A>(
+	agent: &Agent<A>,
+	state: &mut AgentState,
+	step: usize,
+	tx: &Sender<AgentEvent>,
+) -> bool
+where
+	A: 'static + Clone + ApiBase,
+{
+	// Send metadata update
+	let _ = tx.send(AgentEvent::Metadata { data: state.metadata.clone() }).await;
+
+	if let Some(remaining) =
+		agent.options.budget.as_ref().map(|b| AgentBudgetRemaining::new(b, &state.metadata.usage))
+		&& remaining.is_exhausted()
+	{
+		tracing::warn!("agent budget exhausted after step {}", step + 1);
 
-	// TODO raise error.
-	let e = AgentError::MaxStepsExceeded(agent.options.max_steps);
-	let _ = tx.send(AgentEvent::err(e.to_string())).await;
-	let _ = tx
-		.send(AgentEvent::completed(false, state.total_steps(), state.metadata.get_duration()))
-		.await;
+		state.metadata.complete();
 
-	Ok(())
+		let _ = tx
+			.send(AgentEvent::BudgetExceeded { usage: state.metadata.usage.clone(), remaining })
+			.await;
+		let _ = tx
+			.send(AgentEvent::completed(false, state.total_steps(), state.metadata.get_duration()))
+			.await;
+
+		return true;
+	}
+
+	let interval = agent.options.checkpoint_interval;
+
+	if interval > 0 && (step + 1).is_multiple_of(interval) {
+		agent.checkpoint(state).await;
+	}
+
+	false
+}
+
+/// Render reasoning step `i` with its tool call, if any.
+fn format_step(state: &AgentState, i: usize) -> String {
+	let mut text = format!("Thought {}: {}\n", i + 1, state.reasoning_steps[i]);
+
+	// Add corresponding tool call if it exists.
+	let Some(ToolCallResult { tool_call: ToolCall { name, args }, outcome }) =
+		state.tool_calls.get(i)
+	else {
+		return text;
+	};
+
+	text.push_str(&format!("Action: {name}\n"));
+	text.push_str(&format!("Action Input: {args}\n"));
+
+	// Format observation based on tool call outcome.
+	match &outcome {
+		ToolCallOutcome::Success { result } => text.push_str(&format!("Observation: {result}\n")),
+		ToolCallOutcome::Error { message } => {
+			text.push_str(&format!("Error: {message}\n"));
+		},
+	}
+
+	text.push('\n');
+
+	text
+}
+
+/// Keep the first `max_chars` characters of `s`.
+fn truncate_head(s: &str, max_chars: usize) -> String {
+	match s.char_indices().nth(max_chars) {
+		Some((i, _)) => format!("{}\n[truncated]", &s[..i]),
+		None => s.into(),
+	}
+}
+
+/// Keep the first and last `max_chars / 2` characters of `s`.
+fn truncate_head_tail(s: &str, max_chars: usize) -> String {
+	let n = s.chars().count();
+
+	if n <= max_chars {
+		return s.into();
+	}
+
+	let head = max_chars / 2;
+	let tail = max_chars - head;
+	let head_end = s.char_indices().nth(head).map_or(s.len(), |(i, _)| i);
+	let tail_start = s.char_indices().nth(n - tail).map_or(s.len(), |(i, _)| i);
+
+	format!("{}\n[{} characters omitted]\n{}", &s[..head_end], n - max_chars, &s[tail_start..])
 }
 
 /// Extract final answer from reasoning text
@@ -1098,3 +2483,729 @@ fn extract_final_answer(text: &str) -> Option<String> {
 
 	None
 }
+
+#[test]
+fn build_prompt_should_include_plan() {
+	let agent = Agent::builder().build(Api::new(Auth::default()));
+	let mut state = AgentState::new("q".into());
+
+	state.plan =
+		Some(AgentPlan { steps: vec!["look up".into(), "compute".into()], current: 1, replans: 0 });
+
+	let prompt = agent.build_prompt(&state);
+
+	assert!(prompt.contains("1. look up\n2. compute\nCurrent plan step 2: compute\n"));
+
+	state.plan.as_mut().unwrap().current = 2;
+
+	assert!(agent.build_prompt(&state).contains("All plan steps are done"));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn guard_should_work() {
+	let mut agent = Agent::builder().build(Api::new(Auth::default()));
+	let (tx, mut rx) = mpsc::channel(8);
+
+	agent.guardrail(|_, content: String| async move {
+		Ok(GuardrailAction::Replace(content.replace("secret", "***")))
+	});
+	agent.guardrail(|target, content: String| async move {
+		Ok(match target {
+			GuardrailTarget::FinalAnswer if content.contains("***") =>
+				GuardrailAction::Reject("leak".into()),
+			_ => GuardrailAction::Allow,
+		})
+	});
+
+	assert_eq!(agent.guard(GuardrailTarget::Input, "a secret".into(), &tx).await.unwrap(), "a ***");
+	assert!(matches!(
+		agent.guard(GuardrailTarget::FinalAnswer, "a secret".into(), &tx).await,
+		Err(Error::Agent(AgentError::GuardrailRejected { .. }))
+	));
+	assert!(matches!(
+		rx.recv().await,
+		Some(AgentEvent::GuardrailTriggered {
+			target: GuardrailTarget::Input,
+			rejected: false,
+			..
+		})
+	));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn max_concurrent_tools_should_work() {
+	// std
+	use std::sync::Mutex;
+	// crates.io
+	use futures::future::{self, BoxFuture};
+
+	struct Sleep(Arc<Mutex<Vec<&'static str>>>);
+	impl ToolT for Sleep {
+		fn name(&self) -> &str {
+			"sleep"
+		}
+
+		fn description(&self) -> &str {
+			"Sleep a while."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({ "type": "object" })
+		}
+
+		fn call(&self, _: Value) -> BoxFuture<'static, Result<Value>> {
+			let log = self.0.clone();
+
+			Box::pin(async move {
+				log.lock().unwrap().push("start");
+				time::sleep(Duration::from_millis(10)).await;
+				log.lock().unwrap().push("end");
+
+				Ok(Value::Null)
+			})
+		}
+	}
+
+	let mut agent = Agent::builder().max_concurrent_tools(1).build(Api::new(Auth::default()));
+	let (tx, mut rx) = mpsc::channel(8);
+	let log = Arc::new(Mutex::new(Vec::new()));
+
+	agent.register_tool(Sleep(log.clone()));
+
+	let call = || ToolCall { name: "sleep".into(), args: serde_json::json!({}) };
+	let (a, b) = future::join(
+		agent.call_tool_with_timeout(&tx, call()),
+		agent.call_tool_with_timeout(&tx, call()),
+	)
+	.await;
+
+	assert!(a.is_ok() && b.is_ok());
+	// The second call only starts once the first one ended.
+	assert_eq!(*log.lock().unwrap(), ["start", "end", "start", "end"]);
+
+	let mut queue_times = Vec::new();
+
+	for _ in 0..2 {
+		let Some(AgentEvent::ToolResult { queue_time: Some(queue_time), .. }) = rx.recv().await
+		else {
+			panic!("tool results must report their queue time");
+		};
+
+		queue_times.push(queue_time);
+	}
+
+	assert!(queue_times[0] < queue_times[1]);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn deadline_should_work() {
+	let mut agent =
+		Agent::builder().deadline(Duration::from_millis(20)).build(Api::new(Auth::default()));
+
+	agent.guardrail(|_, _| async {
+		time::sleep(Duration::from_secs(1)).await;
+
+		Ok(GuardrailAction::Allow)
+	});
+
+	let events = agent
+		.react_stream(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.collect::<Vec<_>>()
+		.await;
+
+	assert!(matches!(
+		&events[events.len() - 2..],
+		[
+			AgentEvent::Error { message },
+			AgentEvent::Completed { success: false, partial_answer: None, .. },
+		] if message.contains("deadline")
+	));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn streamed_tool_result_should_be_indexed() {
+	// crates.io
+	use futures::{future::BoxFuture, stream::BoxStream};
+
+	struct Count;
+	impl ToolT for Count {
+		fn name(&self) -> &str {
+			"count"
+		}
+
+		fn description(&self) -> &str {
+			"Count to two."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({ "type": "object" })
+		}
+
+		fn call(&self, _: Value) -> BoxFuture<'static, Result<Value>> {
+			Box::pin(async { Ok("12".into()) })
+		}
+
+		fn call_stream(&self, _: Value) -> BoxFuture<'static, Result<BoxStream<'static, String>>> {
+			Box::pin(async { Ok(stream::iter(["1".to_owned(), "2".to_owned()]).boxed()) })
+		}
+
+		fn supports_stream(&self) -> bool {
+			true
+		}
+	}
+
+	let mut agent = Agent::builder().build(Api::new(Auth::default()));
+	let (tx, mut rx) = mpsc::channel(8);
+
+	agent.register_tool(Count);
+	agent
+		.call_tool_with_timeout(&tx, ToolCall { name: "count".into(), args: serde_json::json!({}) })
+		.await
+		.unwrap();
+
+	drop(tx);
+
+	let mut events = Vec::new();
+
+	while let Some(event) = rx.recv().await {
+		events.push(event);
+	}
+
+	assert!(matches!(
+		&events[..],
+		[
+			AgentEvent::ToolResult { chunk: Some(0), .. },
+			AgentEvent::ToolResult { chunk: Some(1), .. },
+			AgentEvent::ToolResultDone { chunks: 2, size: 2, .. },
+		]
+	));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn tool_result_should_be_guarded_before_sent() {
+	// crates.io
+	use futures::{future::BoxFuture, stream::BoxStream};
+
+	struct Leak(bool);
+	impl ToolT for Leak {
+		fn name(&self) -> &str {
+			if self.0 { "stream" } else { "call" }
+		}
+
+		fn description(&self) -> &str {
+			"Leak a secret."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({ "type": "object" })
+		}
+
+		fn call(&self, _: Value) -> BoxFuture<'static, Result<Value>> {
+			Box::pin(async { Ok("a secret".into()) })
+		}
+
+		fn call_stream(&self, _: Value) -> BoxFuture<'static, Result<BoxStream<'static, String>>> {
+			Box::pin(async { Ok(stream::iter(["a se".to_owned(), "cret".to_owned()]).boxed()) })
+		}
+
+		fn supports_stream(&self) -> bool {
+			self.0
+		}
+	}
+
+	let mut agent = Agent::builder().build(Api::new(Auth::default()));
+	let (tx, mut rx) = mpsc::channel(8);
+
+	agent.register_tool(Leak(true));
+	agent.register_tool(Leak(false));
+	agent.guardrail(|_, content: String| async move {
+		Ok(GuardrailAction::Replace(content.replace("secret", "***")))
+	});
+
+	for name in ["stream", "call"] {
+		let res = agent
+			.call_tool_with_timeout(
+				&tx,
+				ToolCall { name: name.into(), args: serde_json::json!({}) },
+			)
+			.await
+			.unwrap();
+
+		assert!(
+			matches!(res.outcome, ToolCallOutcome::Success { result: Value::String(s) } if s == "a ***")
+		);
+	}
+
+	drop(tx);
+
+	let mut results = Vec::new();
+
+	while let Some(event) = rx.recv().await {
+		if let AgentEvent::ToolResult { result, .. } = event {
+			results.push(result);
+		}
+	}
+
+	assert_eq!(results, ["a ***", "a ***"]);
+}
+
+#[cfg(all(test, feature = "jsonschema"))]
+#[tokio::test]
+async fn streamed_tool_arguments_should_be_validated() {
+	// crates.io
+	use futures::{future::BoxFuture, stream::BoxStream};
+
+	struct Echo;
+	impl ToolT for Echo {
+		fn name(&self) -> &str {
+			"echo"
+		}
+
+		fn description(&self) -> &str {
+			"Echo the text."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({
+				"type": "object",
+				"properties": { "text": { "type": "string" } },
+				"required": ["text"],
+			})
+		}
+
+		fn call(&self, _: Value) -> BoxFuture<'static, Result<Value>> {
+			panic!("invalid arguments must not reach the tool");
+		}
+
+		fn call_stream(&self, _: Value) -> BoxFuture<'static, Result<BoxStream<'static, String>>> {
+			panic!("invalid arguments must not reach the tool");
+		}
+
+		fn supports_stream(&self) -> bool {
+			true
+		}
+	}
+
+	let mut agent = Agent::builder().build(Api::new(Auth::default()));
+	let (tx, mut rx) = mpsc::channel(8);
+
+	agent.register_tool(Echo);
+
+	let res = agent
+		.call_tool_with_timeout(&tx, ToolCall { name: "echo".into(), args: serde_json::json!({}) })
+		.await;
+
+	assert!(matches!(res, Err(Error::Tool(ToolError::SchemaViolation { .. }))));
+
+	drop(tx);
+
+	assert!(rx.recv().await.is_none());
+}
+
+#[test]
+fn profile_should_fit_model() {
+	let options = AgentOptions {
+		reasoning_effort: true,
+		max_completion_tokens: 32_000,
+		..Default::default()
+	};
+	assert_eq!(
+		options.profile(&Model::Gpt4o),
+		ModelProfile {
+			temperature: Some(0.7),
+			reasoning_effort: None,
+			max_completion_tokens: 16_384,
+		}
+	);
+	assert_eq!(
+		options.profile(&Model::O3),
+		ModelProfile {
+			temperature: None,
+			reasoning_effort: Some(ReasoningEffort::Medium),
+			max_completion_tokens: 32_000,
+		}
+	);
+}
+
+#[test]
+fn truncate_should_work() {
+	assert_eq!(truncate_head("äbcdef", 3), "äbc\n[truncated]");
+	assert_eq!(truncate_head("äbc", 3), "äbc");
+	assert_eq!(truncate_head_tail("äbcdefg", 4), "äb\n[3 characters omitted]\nfg");
+	assert_eq!(truncate_head_tail("äbc", 4), "äbc");
+}
+
+#[test]
+fn build_prompt_should_skip_summarized_steps() {
+	let agent = Agent::builder().build(Api::new(Auth::default()));
+	let mut state = AgentState::new("q".into());
+
+	state.add_step("a".into());
+	state.add_step("b".into());
+	state.memory_summary = Some("did a".into());
+	state.summarized_steps = 1;
+
+	let prompt = agent.build_prompt(&state);
+
+	assert!(prompt.contains("Summary of earlier steps:\ndid a\n\nThought 2: b\n"));
+	assert!(!prompt.contains("Thought 1:"));
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn reasoning_stream_should_work_with_mock_api() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chunk};
+
+	let api = MockApi::new();
+
+	api.respond(
+		Method::POST,
+		"/chat/completions",
+		MockResponse::Events(vec![mock_chunk("Final "), mock_chunk("Answer: 42")]),
+	);
+
+	let agent = Agent::builder().build(api);
+	let tokens = agent.reasoning_stream_with_prompt("q".into()).await.unwrap();
+
+	assert_eq!(tokens.collect::<String>().await, "Final Answer: 42");
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn hooks_should_work() {
+	// std
+	use std::sync::Mutex;
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chunk};
+
+	let api = MockApi::new();
+	let calls = Arc::new(Mutex::new(Vec::new()));
+	let (steps, answers) = (calls.clone(), calls.clone());
+
+	api.respond(
+		Method::POST,
+		"/chat/completions",
+		MockResponse::Events(vec![mock_chunk("Final Answer: 42")]),
+	);
+
+	let agent = Agent::builder()
+		.on_step_start(move |step, _| {
+			steps.lock().unwrap().push(format!("step {step}"));
+
+			async {}
+		})
+		.on_final_answer(move |answer| {
+			answers.lock().unwrap().push(answer);
+
+			async {}
+		})
+		.build(api);
+	let events = agent.react_stream(AgentState::new("q".into()), CancellationToken::new()).await;
+
+	events.collect::<Vec<_>>().await;
+
+	assert_eq!(*calls.lock().unwrap(), ["step 0", "42"]);
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn error_hook_should_fire_once() {
+	// std
+	use std::sync::Mutex;
+	// self
+	use crate::http::MockApi;
+
+	let errors = Arc::new(Mutex::new(Vec::new()));
+	let agent = Agent::builder()
+		.on_error({
+			let errors = errors.clone();
+
+			move |message| {
+				errors.lock().unwrap().push(message);
+
+				async {}
+			}
+		})
+		// Reasoning fails without any mock response.
+		.build(MockApi::new());
+	let events = agent
+		.react_stream(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.collect::<Vec<_>>()
+		.await;
+
+	assert_eq!(errors.lock().unwrap().len(), 1);
+	assert!(matches!(
+		&events[events.len() - 2..],
+		[AgentEvent::Error { .. }, AgentEvent::Completed { success: false, .. }]
+	));
+	assert_eq!(events.iter().filter(|e| matches!(e, AgentEvent::Error { .. })).count(), 1);
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn resume_should_work() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chunk};
+
+	let api = MockApi::new();
+
+	api.respond(
+		Method::POST,
+		"/chat/completions",
+		MockResponse::Events(vec![mock_chunk("Final Answer: 42")]),
+	);
+
+	let mut agent = Agent::builder().build(api);
+	let mut state = AgentState::new("q".into());
+
+	state.add_step("Thought: look it up".into());
+	agent.state_store(MemoryStateStore::default());
+	agent.checkpoint(&state).await;
+
+	assert!(agent.resume_checkpoint("missing", CancellationToken::new()).await.is_err());
+
+	let events = agent
+		.resume_checkpoint(&state.id, CancellationToken::new())
+		.await
+		.unwrap()
+		.collect::<Vec<_>>()
+		.await;
+
+	assert!(matches!(&events[0], AgentEvent::Resumed { step: 1, .. }));
+	assert!(
+		events.iter().any(|e| matches!(e, AgentEvent::FinalAnswer { content } if content == "42"))
+	);
+
+	let cancellation = CancellationToken::new();
+
+	cancellation.cancel();
+
+	let events = agent.resume(state, cancellation).await.collect::<Vec<_>>().await;
+
+	assert!(matches!(&events[..], [AgentEvent::Completed { success: false, .. }]));
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn budget_should_stop_run() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chunk, mock_usage_chunk};
+
+	let step =
+		|| MockResponse::Events(vec![mock_chunk("Thought: dig deeper"), mock_usage_chunk(10, 5)]);
+	let api = MockApi::new();
+
+	api.respond(Method::POST, "/chat/completions", step())
+		.respond(Method::POST, "/chat/completions", step())
+		.respond(Method::POST, "/chat/completions", step());
+
+	let agent = Agent::builder()
+		.budget(Budget { max_total_tokens: Some(20), ..Default::default() })
+		.build(api.clone());
+	let events = agent
+		.react_stream(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.collect::<Vec<_>>()
+		.await;
+
+	// The first step leaves 5 tokens, the second one uses them up.
+	assert_eq!(api.requests().len(), 2);
+	assert!(matches!(
+		&events[events.len() - 2..],
+		[
+			AgentEvent::BudgetExceeded { usage, remaining },
+			AgentEvent::Completed { success: false, total_steps: 2, .. },
+		] if usage.total_tokens() == 30 && remaining.total_tokens == Some(0)
+	));
+
+	let agent = Agent::builder().max_steps(1).build(api.clone());
+	let events = agent
+		.react_stream(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.collect::<Vec<_>>()
+		.await;
+
+	assert_eq!(api.requests().len(), 3);
+	assert!(matches!(
+		&events[events.len() - 2..],
+		[AgentEvent::Error { .. }, AgentEvent::Completed { success: false, total_steps: 1, .. }]
+	));
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn run_typed_should_work() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chat, mock_chunk};
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Answer {
+		value: u32,
+	}
+	impl TypedAnswer for Answer {
+		fn name() -> &'static str {
+			"answer"
+		}
+
+		fn schema() -> Value {
+			serde_json::json!({
+				"type": "object",
+				"properties": { "value": { "type": "integer" } },
+				"required": ["value"],
+				"additionalProperties": false
+			})
+		}
+	}
+
+	let api = MockApi::new();
+
+	api.respond(
+		Method::POST,
+		"/chat/completions",
+		MockResponse::Events(vec![mock_chunk("Final Answer: forty-two")]),
+	)
+	.respond(Method::POST, "/chat/completions", mock_chat(r#"{"value":42}"#));
+
+	let agent = Agent::builder().build(api.clone());
+	let (answer, usage) = agent
+		.run_typed::<Answer>(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.unwrap();
+
+	assert_eq!(answer, Answer { value: 42 });
+	// The reasoning stream reports no usage, all of it is the structured output call's.
+	assert_eq!(usage.total_tokens(), 2);
+
+	let request = api.requests()[1].body.clone().unwrap();
+
+	assert_eq!(request["response_format"]["json_schema"]["name"], "answer");
+	assert_eq!(request["response_format"]["json_schema"]["strict"], true);
+	assert!(request["messages"][1]["content"].as_str().unwrap().contains("forty-two"));
+
+	// Without a final answer no structured output is requested.
+	let res = Agent::builder()
+		.max_steps(1)
+		.build(api.clone())
+		.run_typed::<Answer>(AgentState::new("q".into()), CancellationToken::new())
+		.await;
+
+	assert!(matches!(res, Err(Error::Agent(AgentError::NoFinalAnswer(_)))));
+	assert_eq!(api.requests().len(), 3);
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn plan_should_be_executed() {
+	// crates.io
+	use futures::future;
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse, mock_chat, mock_chunk};
+
+	let step = |content| MockResponse::Events(vec![mock_chunk(content)]);
+	let api = MockApi::new();
+
+	// The first step calls a missing tool, which fails and replaces the plan.
+	api.respond(
+		Method::POST,
+		"/chat/completions",
+		mock_chat(r#"{"steps":["look it up","answer"]}"#),
+	)
+	.respond(Method::POST, "/chat/completions", step(r#"{"tool":"missing","args":{}}"#))
+	.respond(Method::POST, "/chat/completions", mock_chat(r#"{"steps":["answer"]}"#))
+	.respond(Method::POST, "/chat/completions", step("Final Answer: 42"));
+
+	let agent = Agent::builder()
+		.strategy(AgentStrategy::PlanAndExecute { max_replans: 1 })
+		.build(api.clone());
+	let events = agent
+		.react_stream(AgentState::new("q".into()), CancellationToken::new())
+		.await
+		.filter(|e| {
+			future::ready(matches!(
+				e,
+				AgentEvent::PlanCreated { .. }
+					| AgentEvent::PlanStepStarted { .. }
+					| AgentEvent::FinalAnswer { .. }
+			))
+		})
+		.collect::<Vec<_>>()
+		.await;
+
+	assert!(matches!(
+		&events[..],
+		[
+			AgentEvent::PlanCreated { steps, replanned: false },
+			AgentEvent::PlanStepStarted { index: 0, step: first },
+			AgentEvent::PlanCreated { replanned: true, .. },
+			AgentEvent::PlanStepStarted { index: 0, step: second },
+			AgentEvent::FinalAnswer { content },
+		] if steps.len() == 2 && first == "look it up" && second == "answer" && content == "42"
+	));
+	assert_eq!(api.requests().len(), 4);
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn compress_memory_should_work() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, mock_chat};
+
+	let api = MockApi::new();
+
+	api.respond(Method::POST, "/chat/completions", mock_chat("did a to c"));
+
+	let memory = |max_tokens| MemoryOptions { max_tokens, keep_recent: 1, model: Model::Gpt4oMini };
+	let (tx, mut rx) = mpsc::channel(8);
+	let mut state = AgentState::new("q".into());
+
+	for step in ["a", "b", "c", "d"] {
+		state.add_step(step.into());
+	}
+
+	// Small enough history stays as is.
+	Agent::builder()
+		.memory(memory(1_000))
+		.build(api.clone())
+		.compress_memory(&mut state, &tx)
+		.await;
+
+	assert!(api.requests().is_empty());
+
+	let agent = Agent::builder().memory(memory(1)).build(api.clone());
+
+	agent.compress_memory(&mut state, &tx).await;
+
+	assert_eq!(state.memory_summary.as_deref(), Some("did a to c"));
+	assert_eq!(state.summarized_steps, 3);
+	assert!(matches!(rx.try_recv(), Ok(AgentEvent::MemoryCompressed { from: 0, to: 3 })));
+	assert_eq!(api.requests()[0].body.as_ref().unwrap()["model"], "gpt-4o-mini");
+	// The summary counts towards the run, not towards a reasoning step.
+	assert_eq!(state.metadata.usage.total_tokens(), 2);
+	assert!(state.metadata.step_usage.is_empty());
+
+	// Nothing new to fold.
+	agent.compress_memory(&mut state, &tx).await;
+
+	assert_eq!(api.requests().len(), 1);
+}
@@ -0,0 +1,74 @@
+//! Checks on what enters and leaves an agent run.
+
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::_prelude::*;
+
+/// Hook inspecting the user input, tool results and the final answer of a run.
+///
+/// Guardrails run in registration order, each seeing the content as left by the previous one.
+/// A closure `Fn(GuardrailTarget, String) -> impl Future<Output = Result<GuardrailAction>>` is a
+/// guardrail, e.g. one flagging content through a moderation endpoint.
+pub trait Guardrail
+where
+	Self: Send + Sync,
+{
+	/// Decide what happens to `content`.
+	fn check(
+		&self,
+		target: &GuardrailTarget,
+		content: &str,
+	) -> BoxFuture<'static, Result<GuardrailAction>>;
+}
+impl<F, Fut> Guardrail for F
+where
+	F: Send + Sync + Fn(GuardrailTarget, String) -> Fut,
+	Fut: 'static + Send + Future<Output = Result<GuardrailAction>>,
+{
+	fn check(
+		&self,
+		target: &GuardrailTarget,
+		content: &str,
+	) -> BoxFuture<'static, Result<GuardrailAction>> {
+		Box::pin(self(target.clone(), content.into()))
+	}
+}
+
+/// Content a [`Guardrail`] is checking.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GuardrailTarget {
+	/// The user input of the run.
+	Input,
+	/// Result of a successful call to `tool`.
+	ToolResult {
+		/// Name of the tool.
+		tool: String,
+	},
+	/// The final answer.
+	FinalAnswer,
+}
+impl Display for GuardrailTarget {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::Input => write!(f, "input"),
+			Self::ToolResult { tool } => write!(f, "result of tool '{tool}'"),
+			Self::FinalAnswer => write!(f, "final answer"),
+		}
+	}
+}
+
+/// Verdict of a [`Guardrail`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuardrailAction {
+	/// Keep the content as is.
+	Allow,
+	/// Go on with the given content instead, e.g. with personal data masked.
+	Replace(String),
+	/// Refuse the content.
+	///
+	/// A rejected tool result is fed back to the model as a failed call, any other rejection
+	/// ends the run with [`AgentError::GuardrailRejected`].
+	Reject(String),
+}
@@ -0,0 +1,78 @@
+//! Callbacks run at points of an agent run.
+
+// std
+use std::sync::Arc;
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::{_prelude::*, agent::AgentState, tool::ToolCallResult};
+
+type Hook<T> = Arc<dyn Send + Sync + Fn(T) -> BoxFuture<'static, ()>>;
+
+/// Callbacks of an agent, registered through the `on_*` methods of `AgentBuilder`.
+///
+/// Hooks are awaited in registration order before the run goes on.
+#[derive(Clone, Default)]
+pub(super) struct AgentHooks {
+	step_start: Vec<Hook<(usize, AgentState)>>,
+	tool_result: Vec<Hook<ToolCallResult>>,
+	final_answer: Vec<Hook<String>>,
+	error: Vec<Hook<String>>,
+}
+impl AgentHooks {
+	pub(super) fn on_step_start<F, Fut>(&mut self, hook: F)
+	where
+		F: 'static + Send + Sync + Fn(usize, AgentState) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.step_start.push(Arc::new(move |(step, state)| Box::pin(hook(step, state))));
+	}
+
+	pub(super) fn on_tool_result<F, Fut>(&mut self, hook: F)
+	where
+		F: 'static + Send + Sync + Fn(ToolCallResult) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.tool_result.push(Arc::new(move |result| Box::pin(hook(result))));
+	}
+
+	pub(super) fn on_final_answer<F, Fut>(&mut self, hook: F)
+	where
+		F: 'static + Send + Sync + Fn(String) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.final_answer.push(Arc::new(move |answer| Box::pin(hook(answer))));
+	}
+
+	pub(super) fn on_error<F, Fut>(&mut self, hook: F)
+	where
+		F: 'static + Send + Sync + Fn(String) -> Fut,
+		Fut: 'static + Send + Future<Output = ()>,
+	{
+		self.error.push(Arc::new(move |message| Box::pin(hook(message))));
+	}
+
+	pub(super) async fn step_start(&self, step: usize, state: &AgentState) {
+		for hook in &self.step_start {
+			hook((step, state.clone())).await;
+		}
+	}
+
+	pub(super) async fn tool_result(&self, result: &ToolCallResult) {
+		for hook in &self.tool_result {
+			hook(result.clone()).await;
+		}
+	}
+
+	pub(super) async fn final_answer(&self, answer: &str) {
+		for hook in &self.final_answer {
+			hook(answer.into()).await;
+		}
+	}
+
+	pub(super) async fn error(&self, message: &str) {
+		for hook in &self.error {
+			hook(message.into()).await;
+		}
+	}
+}
@@ -0,0 +1,106 @@
+//! Long-term agent memory backed by embeddings.
+
+// std
+use std::sync::Mutex;
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::_prelude::*;
+
+/// Store of embedded memories searchable by similarity.
+pub trait VectorMemory
+where
+	Self: Send + Sync,
+{
+	/// Add `entry`.
+	fn insert(&self, entry: MemoryEntry) -> BoxFuture<'_, Result<()>>;
+
+	/// Return the `k` entries most similar to `embedding`, best first.
+	fn search(&self, embedding: Vec<f32>, k: usize) -> BoxFuture<'_, Result<Vec<ScoredMemory>>>;
+}
+
+/// Memory stored in a [`VectorMemory`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEntry {
+	/// Remembered text.
+	pub text: String,
+	/// Embedding of `text`.
+	pub embedding: Vec<f32>,
+}
+
+/// Search hit of a [`VectorMemory`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoredMemory {
+	/// Remembered text.
+	pub text: String,
+	/// Cosine similarity to the query.
+	pub score: f32,
+}
+
+/// In-memory [`VectorMemory`] doing an exhaustive search, fine for some thousand entries.
+#[derive(Debug, Default)]
+pub struct InMemoryVectorMemory(Mutex<Vec<MemoryEntry>>);
+impl VectorMemory for InMemoryVectorMemory {
+	fn insert(&self, entry: MemoryEntry) -> BoxFuture<'_, Result<()>> {
+		self.0.lock().expect("lock must succeed; qed").push(entry);
+
+		Box::pin(async { Ok(()) })
+	}
+
+	fn search(&self, embedding: Vec<f32>, k: usize) -> BoxFuture<'_, Result<Vec<ScoredMemory>>> {
+		let mut hits = self
+			.0
+			.lock()
+			.expect("lock must succeed; qed")
+			.iter()
+			.map(|e| ScoredMemory {
+				text: e.text.clone(),
+				score: cosine_similarity(&embedding, &e.embedding),
+			})
+			.collect::<Vec<_>>();
+
+		hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+		hits.truncate(k);
+
+		Box::pin(async { Ok(hits) })
+	}
+}
+
+/// Options of the [`VectorMemory`] of an agent, see [`crate::agent::Agent::vector_memory`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VectorMemoryOptions {
+	/// Embedding model.
+	pub model: Model,
+	/// Number of memories recalled into each prompt.
+	pub top_k: usize,
+}
+impl Default for VectorMemoryOptions {
+	fn default() -> Self {
+		Self { model: Model::TextEmbedding3Small, top_k: 5 }
+	}
+}
+
+/// Cosine similarity of `a` and `b`, `0` if either is zero.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+	let (dot, a2, b2) = a
+		.iter()
+		.zip(b)
+		.fold((0., 0., 0.), |(d, a2, b2), (x, y)| (d + x * y, a2 + x * x, b2 + y * y));
+
+	if a2 == 0. || b2 == 0. { 0. } else { dot / (a2 * b2).sqrt() }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn in_memory_vector_memory_should_work() {
+	let memory = InMemoryVectorMemory::default();
+
+	for (text, embedding) in [("x", vec![1., 0.]), ("y", vec![0., 1.]), ("xy", vec![1., 1.])] {
+		memory.insert(MemoryEntry { text: text.into(), embedding }).await.unwrap();
+	}
+
+	let hits = memory.search(vec![1., 0.1], 2).await.unwrap();
+
+	assert_eq!(hits.iter().map(|h| h.text.as_str()).collect::<Vec<_>>(), ["x", "xy"]);
+	assert!(hits[0].score > 0.99);
+}
@@ -0,0 +1,63 @@
+//! Tracing spans of agent steps, LLM calls and tool calls.
+//!
+//! With the `otel` feature, steps, LLM calls and tool calls run in spans carrying token counts
+//! and durations, ready to be exported through `tracing-opentelemetry`. Without it, all spans
+//! are disabled.
+
+// std
+use std::time::Instant;
+// crates.io
+use tracing::Span;
+// self
+use crate::{_prelude::*, agent::AgentUsage};
+
+pub(super) fn step(#[allow(unused)] step: usize) -> Span {
+	#[cfg(feature = "otel")]
+	return tracing::info_span!(
+		"agent.step",
+		agent.step = step + 1,
+		gen_ai.usage.input_tokens = tracing::field::Empty,
+		gen_ai.usage.output_tokens = tracing::field::Empty,
+		gen_ai.usage.reasoning_tokens = tracing::field::Empty,
+		duration_ms = tracing::field::Empty,
+	);
+	#[cfg(not(feature = "otel"))]
+	Span::none()
+}
+
+pub(super) fn llm_call(#[allow(unused)] model: &Model) -> Span {
+	#[cfg(feature = "otel")]
+	return tracing::info_span!(
+		"gen_ai.chat",
+		gen_ai.operation.name = "chat",
+		gen_ai.request.model = %model,
+		gen_ai.usage.input_tokens = tracing::field::Empty,
+		gen_ai.usage.output_tokens = tracing::field::Empty,
+		gen_ai.usage.reasoning_tokens = tracing::field::Empty,
+		duration_ms = tracing::field::Empty,
+	);
+	#[cfg(not(feature = "otel"))]
+	Span::none()
+}
+
+pub(super) fn tool_call(#[allow(unused)] name: &str) -> Span {
+	#[cfg(feature = "otel")]
+	return tracing::info_span!(
+		"agent.tool_call",
+		gen_ai.tool.name = name,
+		success = tracing::field::Empty,
+		duration_ms = tracing::field::Empty,
+	);
+	#[cfg(not(feature = "otel"))]
+	Span::none()
+}
+
+pub(super) fn record_usage(span: &Span, usage: &AgentUsage) {
+	span.record("gen_ai.usage.input_tokens", usage.prompt_tokens)
+		.record("gen_ai.usage.output_tokens", usage.completion_tokens)
+		.record("gen_ai.usage.reasoning_tokens", usage.reasoning_tokens);
+}
+
+pub(super) fn record_duration(span: &Span, start: Instant) {
+	span.record("duration_ms", start.elapsed().as_millis() as u64);
+}
@@ -0,0 +1,137 @@
+//! Persistence of [`AgentState`] across process restarts.
+
+// std
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::{_prelude::*, agent::AgentState};
+
+/// Storage for agent checkpoints, keyed by [`AgentState::id`].
+pub trait StateStore
+where
+	Self: Send + Sync,
+{
+	/// Save `state`, replacing any previous checkpoint with the same ID.
+	fn save(&self, state: &AgentState) -> BoxFuture<'_, Result<()>>;
+
+	/// Load the checkpoint saved under `id`, if any.
+	fn load(&self, id: &str) -> BoxFuture<'_, Result<Option<AgentState>>>;
+
+	/// Remove the checkpoint saved under `id`.
+	fn delete(&self, id: &str) -> BoxFuture<'_, Result<()>>;
+}
+
+/// In-memory [`StateStore`], checkpoints are lost with the process.
+#[derive(Debug, Default)]
+pub struct MemoryStateStore(Mutex<HashMap<String, AgentState>>);
+impl StateStore for MemoryStateStore {
+	fn save(&self, state: &AgentState) -> BoxFuture<'_, Result<()>> {
+		self.0.lock().expect("lock must succeed; qed").insert(state.id.clone(), state.clone());
+
+		Box::pin(async { Ok(()) })
+	}
+
+	fn load(&self, id: &str) -> BoxFuture<'_, Result<Option<AgentState>>> {
+		let state = self.0.lock().expect("lock must succeed; qed").get(id).cloned();
+
+		Box::pin(async { Ok(state) })
+	}
+
+	fn delete(&self, id: &str) -> BoxFuture<'_, Result<()>> {
+		self.0.lock().expect("lock must succeed; qed").remove(id);
+
+		Box::pin(async { Ok(()) })
+	}
+}
+
+/// [`StateStore`] writing one JSON file per run into a directory.
+///
+/// Files are replaced atomically, so a crash mid-write keeps the previous checkpoint.
+#[derive(Debug)]
+pub struct FileStateStore {
+	dir: PathBuf,
+}
+impl FileStateStore {
+	/// Create a store in `dir`, which is created on the first save.
+	pub fn new<P>(dir: P) -> Self
+	where
+		P: Into<PathBuf>,
+	{
+		Self { dir: dir.into() }
+	}
+
+	/// Rejects IDs which could name a file outside of the directory.
+	fn path(&self, id: &str) -> Result<PathBuf> {
+		if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+			Err(Error::any(format!("invalid state id: {id}")))?;
+		}
+
+		Ok(self.dir.join(format!("{id}.json")))
+	}
+}
+impl StateStore for FileStateStore {
+	fn save(&self, state: &AgentState) -> BoxFuture<'_, Result<()>> {
+		let json = serde_json::to_vec(state);
+		let (path, tmp) = (self.path(&state.id), self.dir.join(format!(".{}.json.tmp", state.id)));
+
+		Box::pin(async move {
+			let path = path?;
+
+			tokio::fs::create_dir_all(&self.dir).await?;
+			tokio::fs::write(&tmp, json?).await?;
+			tokio::fs::rename(&tmp, &path).await?;
+
+			Ok(())
+		})
+	}
+
+	fn load(&self, id: &str) -> BoxFuture<'_, Result<Option<AgentState>>> {
+		let path = self.path(id);
+
+		Box::pin(async move {
+			match tokio::fs::read(path?).await {
+				Ok(json) => Ok(Some(serde_json::from_slice(&json)?)),
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+				Err(e) => Err(e)?,
+			}
+		})
+	}
+
+	fn delete(&self, id: &str) -> BoxFuture<'_, Result<()>> {
+		let path = self.path(id);
+
+		Box::pin(async move {
+			match tokio::fs::remove_file(path?).await {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e)?,
+				_ => Ok(()),
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn file_state_store_should_work() {
+	let dir = std::env::temp_dir().join(format!("openagent-{}", uuid::Uuid::new_v4()));
+	let store = FileStateStore::new(&dir);
+	let mut state = AgentState::new("foo".into());
+
+	state.add_step("bar".into());
+	state.add_tool_call(crate::tool::ToolCallResult::err("baz".into(), Value::Null, "qux".into()));
+	store.save(&state).await.unwrap();
+
+	let loaded = store.load(&state.id).await.unwrap().unwrap();
+
+	assert_eq!(loaded.reasoning_steps, ["bar"]);
+	assert_eq!(loaded.tool_calls.len(), 1);
+	assert_eq!(loaded.metadata.total_steps, 1);
+
+	store.delete(&state.id).await.unwrap();
+
+	assert!(store.load(&state.id).await.unwrap().is_none());
+	assert!(store.load("../foo").await.is_err());
+	assert!(store.delete("foo/bar").await.is_err());
+
+	let _ = std::fs::remove_dir_all(dir);
+}
@@ -2,6 +2,7 @@
 
 pub mod batch;
 pub mod chat;
+pub mod container;
 pub mod embedding;
 pub mod file;
 pub mod response;
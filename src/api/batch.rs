@@ -2,13 +2,26 @@
 //!
 //! <https://platform.openai.com/docs/api-reference/batch>
 
+// std
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
 // self
-use crate::_prelude::*;
+use crate::{
+	_prelude::*,
+	api::{
+		chat::{ChatObject, ChatRequest},
+		embedding::{EmbeddingRequest, EmbeddingResponse},
+		file::ApiFile,
+		response::{ResponseObject, ResponseRequest},
+	},
+};
 
 /// OpenAI batches API.
 pub trait ApiBatch
 where
-	Self: ApiBase,
+	Self: ApiBase + ApiFile,
 {
 	/// Create a batch.
 	fn create_batch(
@@ -34,6 +47,125 @@ where
 			Ok(serde_json::from_str::<ApiResult<BatchObject>>(&resp)?.as_result()?)
 		}
 	}
+
+	/// Cancel an in-progress batch by its ID.
+	fn cancel_batch(&self, id: &str) -> impl Send + Future<Output = Result<BatchObject>> {
+		async move {
+			let resp = self.post_json(&format!("/batches/{id}/cancel"), Map::new()).await?;
+
+			tracing::debug!("{resp}");
+
+			Ok(serde_json::from_str::<ApiResult<BatchObject>>(&resp)?.as_result()?)
+		}
+	}
+
+	/// Poll a batch until it reaches a terminal status.
+	///
+	/// The polling interval doubles after every attempt, capped at `options.max_interval`.
+	fn wait_for_batch(
+		&self,
+		id: &str,
+		options: PollOptions,
+	) -> impl Send + Future<Output = Result<BatchObject>> {
+		async move {
+			let start = Instant::now();
+			let mut interval = options.interval;
+
+			loop {
+				let batch = self.retrieve_batch(id).await?;
+
+				if let Some(on_progress) = &options.on_progress {
+					on_progress(&batch);
+				}
+				if batch.is_terminal() {
+					return Ok(batch);
+				}
+				if let Some(timeout) = options.timeout
+					&& start.elapsed() + interval > timeout
+				{
+					return Err(Error::Timeout(timeout));
+				}
+
+				tokio::time::sleep(interval).await;
+
+				interval = (interval * 2).min(options.max_interval);
+			}
+		}
+	}
+
+	/// Retrieve and parse a batch output or error file.
+	///
+	/// `T` is the response body type of the batch endpoint, see [`BatchBody::Output`].
+	fn retrieve_batch_output<T>(
+		&self,
+		file_id: &str,
+	) -> impl Send + Future<Output = Result<Vec<BatchOutput<T>>>>
+	where
+		T: DeserializeOwned,
+	{
+		async move {
+			let resp = self.retrieve_file_content(file_id).await?;
+
+			BatchOutput::parse_jsonl(&resp)
+		}
+	}
+
+	/// Run a batch from start to finish.
+	///
+	/// Uploads `inputs`, creates the batch, waits for it and returns the parsed output and error
+	/// records in the order of `inputs`. The endpoint is taken from the first input.
+	fn run_batch<B>(
+		&self,
+		inputs: Vec<BatchInput<B>>,
+		options: PollOptions,
+	) -> impl Send + Future<Output = Result<Vec<BatchOutput<B::Output>>>>
+	where
+		B: Send + BatchBody,
+		B::Output: Send,
+	{
+		async move {
+			let Some(endpoint) = inputs.first().map(|i| i.url.clone()) else {
+				return Err(Error::any("batch inputs must not be empty"));
+			};
+			let order = inputs
+				.iter()
+				.enumerate()
+				.map(|(i, input)| (input.custom_id.clone(), i))
+				.collect::<HashMap<_, _>>();
+			let content = inputs
+				.iter()
+				.map(serde_json::to_string)
+				.collect::<Result<Vec<_>, _>>()?
+				.join("\n")
+				.into_bytes();
+			let file = self.upload_file("batch.jsonl", content, Purpose::Batch, None).await?;
+			let batch = self
+				.create_batch(BatchRequest {
+					endpoint,
+					input_file_id: file.id,
+					..Default::default()
+				})
+				.await?;
+			let batch = self.wait_for_batch(&batch.id, options).await?;
+
+			if batch.output_file_id.is_none() && batch.error_file_id.is_none() {
+				return Err(Error::any(format!(
+					"batch {} ended with status {} and produced no output",
+					batch.id, batch.status
+				)));
+			}
+
+			let mut outputs = Vec::with_capacity(order.len());
+
+			for file_id in [&batch.output_file_id, &batch.error_file_id].into_iter().flatten() {
+				outputs.extend(self.retrieve_batch_output(file_id).await?);
+			}
+
+			outputs.sort_by_key(|o| order.get(&o.custom_id).copied().unwrap_or(usize::MAX));
+
+			Ok(outputs)
+		}
+	}
 }
 impl<T> ApiBatch for T where T: ApiBase {}
 
@@ -54,7 +186,7 @@ impl_const_str! {
 impl_serializable_deserializable_enum! {
 	Endpoint {
 		#[default]
-		Response => "/v1/response",
+		Responses => "/v1/responses",
 		ChatCompletion => "/v1/chat/completions",
 		Embeddings => "/v1/embeddings",
 		Completions => "/v1/completions",
@@ -70,10 +202,50 @@ pub struct BatchInput<T> {
 	pub body: T,
 }
 
+impl<T> BatchInput<T>
+where
+	T: BatchBody,
+{
+	/// Creates a batch input targeting the endpoint of `T`.
+	pub fn new<S>(custom_id: S, body: T) -> Self
+	where
+		S: Into<String>,
+	{
+		Self { custom_id: custom_id.into(), method: Default::default(), url: T::ENDPOINT, body }
+	}
+}
+
 impl_const_str! {
 	Post => "POST",
 }
 
+/// Request body which can be submitted through a batch.
+pub trait BatchBody
+where
+	Self: Serialize,
+{
+	/// Endpoint serving this body.
+	const ENDPOINT: Endpoint;
+
+	/// Response body returned by the endpoint.
+	type Output: DeserializeOwned;
+}
+impl BatchBody for ChatRequest {
+	type Output = ChatObject;
+
+	const ENDPOINT: Endpoint = Endpoint::ChatCompletion;
+}
+impl BatchBody for EmbeddingRequest {
+	type Output = EmbeddingResponse;
+
+	const ENDPOINT: Endpoint = Endpoint::Embeddings;
+}
+impl BatchBody for ResponseRequest {
+	type Output = ResponseObject;
+
+	const ENDPOINT: Endpoint = Endpoint::Responses;
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct BatchObject {
@@ -101,6 +273,13 @@ pub struct BatchObject {
 	pub status: String,
 }
 
+impl BatchObject {
+	/// Whether the batch has stopped processing.
+	pub fn is_terminal(&self) -> bool {
+		matches!(self.status.as_str(), "completed" | "failed" | "expired" | "cancelled")
+	}
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct BatchError {
@@ -124,3 +303,148 @@ pub struct RequestCounts {
 	pub failed: u32,
 	pub total: u32,
 }
+
+/// Progress callback of [`ApiBatch::wait_for_batch`].
+pub type OnProgress = Box<dyn Send + Sync + Fn(&BatchObject)>;
+
+/// Options controlling [`ApiBatch::wait_for_batch`].
+pub struct PollOptions {
+	/// Delay before the second poll.
+	pub interval: Duration,
+	/// Upper bound of the delay between two polls.
+	pub max_interval: Duration,
+	/// Give up with [`Error::Timeout`] once this much time has passed.
+	pub timeout: Option<Duration>,
+	/// Called with the batch after every poll.
+	pub on_progress: Option<OnProgress>,
+}
+impl PollOptions {
+	/// Sets the initial polling interval.
+	pub fn interval(mut self, interval: Duration) -> Self {
+		self.interval = interval;
+
+		self
+	}
+
+	/// Sets the maximum polling interval.
+	pub fn max_interval(mut self, max_interval: Duration) -> Self {
+		self.max_interval = max_interval;
+
+		self
+	}
+
+	/// Sets the overall timeout.
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+
+		self
+	}
+
+	/// Sets the progress callback.
+	pub fn on_progress<F>(mut self, on_progress: F) -> Self
+	where
+		F: 'static + Send + Sync + Fn(&BatchObject),
+	{
+		self.on_progress = Some(Box::new(on_progress));
+
+		self
+	}
+}
+impl Default for PollOptions {
+	fn default() -> Self {
+		Self {
+			interval: Duration::from_secs(5),
+			max_interval: Duration::from_secs(300),
+			timeout: None,
+			on_progress: None,
+		}
+	}
+}
+
+/// A single line of a batch output or error file.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct BatchOutput<T> {
+	pub custom_id: String,
+	pub error: Option<BatchOutputError>,
+	pub id: String,
+	pub response: Option<BatchOutputResponse<T>>,
+}
+impl<T> BatchOutput<T>
+where
+	T: DeserializeOwned,
+{
+	/// Parse the JSONL content of a batch output or error file.
+	pub fn parse_jsonl(content: &str) -> Result<Vec<Self>> {
+		content
+			.lines()
+			.filter(|l| !l.trim().is_empty())
+			.map(|l| Ok(serde_json::from_str(l)?))
+			.collect()
+	}
+}
+impl<T> BatchOutput<T> {
+	/// Converts the record into the response body or the error which prevented it.
+	pub fn into_result(self) -> Result<T> {
+		if let Some(e) = self.error {
+			return Err(Error::any(format!("batch request {} failed: {e}", self.custom_id)));
+		}
+
+		match self.response {
+			Some(r) => Ok(r.body.as_result()?),
+			None => Err(Error::any(format!("batch request {} has no response", self.custom_id))),
+		}
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct BatchOutputResponse<T> {
+	pub body: ApiResult<T>,
+	pub request_id: String,
+	pub status_code: u16,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize)]
+pub struct BatchOutputError {
+	pub code: Option<String>,
+	pub message: String,
+}
+impl Display for BatchOutputError {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match &self.code {
+			Some(c) => write!(f, "{c}: {}", self.message),
+			None => write!(f, "{}", self.message),
+		}
+	}
+}
+
+#[test]
+fn batch_output_should_work() {
+	let content = r#"{"id":"batch_req_1","custom_id":"request-1","response":{"status_code":200,"request_id":"req_1","body":{"object":"list","data":[{"object":"embedding","embedding":[0.1,0.2],"index":0}],"model":"text-embedding-3-small","usage":{"prompt_tokens":2,"total_tokens":2}}},"error":null}
+{"id":"batch_req_2","custom_id":"request-2","response":{"status_code":400,"request_id":"req_2","body":{"error":{"message":"Invalid input.","type":"invalid_request_error","param":null,"code":null}}},"error":null}
+{"id":"batch_req_3","custom_id":"request-3","response":null,"error":{"code":"batch_expired","message":"This request could not be executed before the completion window expired."}}
+"#;
+	let mut outputs = BatchOutput::<EmbeddingResponse>::parse_jsonl(content).unwrap().into_iter();
+
+	assert_eq!(outputs.next().unwrap().into_result().unwrap().data[0].embedding, [0.1, 0.2]);
+	assert!(matches!(outputs.next().unwrap().into_result(), Err(Error::Api(_))));
+	assert!(outputs.next().unwrap().into_result().is_err());
+}
+
+#[test]
+fn response_batch_should_work() {
+	let input = BatchInput::new("request-1", ResponseRequest::default());
+
+	assert_eq!(serde_json::to_value(&input).unwrap()["url"], "/v1/responses");
+
+	let content = r#"{"id":"batch_req_1","custom_id":"request-1","response":{"status_code":200,"request_id":"req_1","body":{"id":"resp_1","object":"response","created_at":1741476542,"status":"completed","error":null,"incomplete_details":null,"instructions":null,"max_output_tokens":null,"model":"gpt-4.1","output":[],"parallel_tool_calls":true,"previous_response_id":null,"reasoning":null,"temperature":1.0,"text":{"format":{"type":"text"}},"tool_choice":"auto","tools":[],"top_p":1.0,"truncation":"disabled","usage":null,"user":null,"metadata":{}}},"error":null}"#;
+	let output = BatchOutput::<<ResponseRequest as BatchBody>::Output>::parse_jsonl(content)
+		.unwrap()
+		.remove(0)
+		.into_result()
+		.unwrap();
+
+	assert_eq!(output.id, "resp_1");
+}
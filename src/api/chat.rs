@@ -74,10 +74,14 @@ pub struct ChatRequest {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub presence_penalty: Option<f32>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub prompt_cache_key: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub reasoning_effort: Option<ReasoningEffort>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub response_format: Option<ChatResponseFormat>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub safety_identifier: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub seed: Option<u64>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub service_tier: Option<ServiceTier>,
@@ -0,0 +1,56 @@
+//! OpenAI Containers API
+//!
+//! <https://platform.openai.com/docs/api-reference/containers>
+
+// crates.io
+use tokio::io::AsyncWrite;
+use tokio_util::bytes::Bytes;
+// self
+use crate::{_prelude::*, api::file::write_stream};
+
+/// OpenAI containers API.
+pub trait ApiContainer
+where
+	Self: ApiBase,
+{
+	/// Retrieve a container file content as raw bytes.
+	fn retrieve_container_file_content(
+		&self,
+		container_id: &str,
+		file_id: &str,
+	) -> impl Send + Future<Output = Result<Bytes>> {
+		async move {
+			self.get_bytes(&format!("/containers/{container_id}/files/{file_id}/content")).await
+		}
+	}
+
+	/// Retrieve a container file content as a byte stream, without buffering it in memory.
+	fn retrieve_container_file_content_stream(
+		&self,
+		container_id: &str,
+		file_id: &str,
+	) -> impl Send + Future<Output = Result<EventStream<Bytes>>> {
+		async move {
+			self.get_bytes_stream(&format!("/containers/{container_id}/files/{file_id}/content"))
+				.await
+		}
+	}
+
+	/// Stream a container file content into `writer`, returning the number of bytes written.
+	fn download_container_file<W>(
+		&self,
+		container_id: &str,
+		file_id: &str,
+		mut writer: W,
+	) -> impl Send + Future<Output = Result<u64>>
+	where
+		W: Send + Unpin + AsyncWrite,
+	{
+		async move {
+			let stream = self.retrieve_container_file_content_stream(container_id, file_id).await?;
+
+			write_stream(stream, &mut writer).await
+		}
+	}
+}
+impl<T> ApiContainer for T where T: ApiBase {}
@@ -2,6 +2,8 @@
 //!
 //! <https://platform.openai.com/docs/api-reference/embeddings>
 
+// crates.io
+use futures::{StreamExt, TryStreamExt, stream};
 // self
 use crate::_prelude::*;
 
@@ -23,9 +25,304 @@ where
 			Ok(serde_json::from_str::<ApiResult<EmbeddingResponse>>(&resp)?.as_result()?)
 		}
 	}
+
+	/// Embed a large set of inputs by splitting them into API-sized batches.
+	///
+	/// `request` acts as a template, its `input` is replaced per batch. Batches run with bounded
+	/// concurrency, and the merged response keeps the original input order with summed usage.
+	fn create_embedding_batched<I>(
+		&self,
+		request: EmbeddingRequest,
+		inputs: I,
+		options: EmbeddingBatchOptions,
+	) -> impl Send + Future<Output = Result<EmbeddingResponse>>
+	where
+		I: IntoIterator,
+		I::Item: Into<String>,
+	{
+		let batch_size = options.batch_size.max(1);
+		let mut batches = Vec::<Vec<String>>::new();
+
+		for input in inputs {
+			match batches.last_mut() {
+				Some(batch) if batch.len() < batch_size => batch.push(input.into()),
+				_ => batches.push(vec![input.into()]),
+			}
+		}
+
+		async move {
+			let mut merged = EmbeddingResponse {
+				data: Vec::new(),
+				model: request.model.clone(),
+				usage: EmbeddingUsage { prompt_tokens: 0, total_tokens: 0 },
+			};
+			let mut resps = stream::iter(batches)
+				.map(|input| {
+					self.create_embedding(EmbeddingRequest {
+						input: Either::B(input),
+						..request.clone()
+					})
+				})
+				.buffered(options.concurrency.max(1));
+
+			while let Some(resp) = resps.try_next().await? {
+				let offset = merged.data.len() as u32;
+				let mut data = resp.data;
+
+				// Indices are relative to the batch, shift them to the original position.
+				data.sort_by_key(|o| o.index);
+				data.iter_mut().for_each(|o| o.index += offset);
+
+				merged.data.extend(data);
+				merged.model = resp.model;
+				merged.usage.prompt_tokens += resp.usage.prompt_tokens;
+				merged.usage.total_tokens += resp.usage.total_tokens;
+			}
+
+			Ok(merged)
+		}
+	}
+
+	/// Split a long text into token-bounded chunks and embed each of them.
+	///
+	/// Returns one [`EmbeddingChunk`] per chunk, carrying its byte offsets in `text`.
+	fn create_embedding_chunked(
+		&self,
+		request: EmbeddingRequest,
+		text: &str,
+		chunk_options: ChunkOptions,
+		batch_options: EmbeddingBatchOptions,
+	) -> impl Send + Future<Output = Result<Vec<EmbeddingChunk>>> {
+		let chunks = chunk_text(text, &chunk_options);
+		let inputs = chunks.iter().map(|c| c.text.clone()).collect::<Vec<_>>();
+
+		async move {
+			let resp = self.create_embedding_batched(request, inputs, batch_options).await?;
+
+			Ok(chunks
+				.into_iter()
+				.zip(resp.data)
+				.map(|(chunk, o)| EmbeddingChunk { chunk, embedding: o.embedding })
+				.collect())
+		}
+	}
 }
 impl<T> ApiEmbedding for T where T: ApiBase {}
 
+/// Options controlling [`ApiEmbedding::create_embedding_batched`].
+#[derive(Clone, Debug)]
+pub struct EmbeddingBatchOptions {
+	/// Maximum number of inputs sent in a single request.
+	pub batch_size: usize,
+	/// Maximum number of requests in flight at the same time.
+	pub concurrency: usize,
+}
+impl EmbeddingBatchOptions {
+	/// Sets the maximum number of inputs per request.
+	pub fn batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = batch_size;
+
+		self
+	}
+
+	/// Sets the maximum number of concurrent requests.
+	pub fn concurrency(mut self, concurrency: usize) -> Self {
+		self.concurrency = concurrency;
+
+		self
+	}
+}
+impl Default for EmbeddingBatchOptions {
+	fn default() -> Self {
+		// The embeddings API accepts at most 2048 inputs per request.
+		Self { batch_size: 2048, concurrency: 4 }
+	}
+}
+
+/// Options controlling [`chunk_text`].
+#[derive(Clone, Debug)]
+pub struct ChunkOptions {
+	/// Maximum number of tokens per chunk.
+	pub max_tokens: usize,
+	/// Number of tokens repeated from the end of the previous chunk.
+	pub overlap_tokens: usize,
+	/// Function used to count the tokens of a piece of text.
+	pub count_tokens: fn(&str) -> usize,
+}
+impl ChunkOptions {
+	/// Sets the maximum number of tokens per chunk.
+	pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+		self.max_tokens = max_tokens;
+
+		self
+	}
+
+	/// Sets the number of overlapping tokens between consecutive chunks.
+	pub fn overlap_tokens(mut self, overlap_tokens: usize) -> Self {
+		self.overlap_tokens = overlap_tokens;
+
+		self
+	}
+
+	/// Replaces the token counter.
+	pub fn count_tokens(mut self, count_tokens: fn(&str) -> usize) -> Self {
+		self.count_tokens = count_tokens;
+
+		self
+	}
+}
+impl Default for ChunkOptions {
+	fn default() -> Self {
+		// Input limit of the `text-embedding-3-*` and `text-embedding-ada-002` models.
+		Self { max_tokens: 8191, overlap_tokens: 0, count_tokens: bound_tokens }
+	}
+}
+
+/// A slice of a longer text produced by [`chunk_text`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextChunk {
+	/// Content of the chunk.
+	pub text: String,
+	/// Byte offset of the chunk start in the source text.
+	pub start: usize,
+	/// Byte offset of the chunk end in the source text (exclusive).
+	pub end: usize,
+}
+
+/// A text chunk together with its embedding vector.
+#[derive(Clone, Debug)]
+pub struct EmbeddingChunk {
+	/// The embedded chunk.
+	pub chunk: TextChunk,
+	/// Embedding vector of the chunk.
+	pub embedding: Vec<f32>,
+}
+
+/// Rough token estimate, assuming three bytes per ASCII token and one token per other character.
+///
+/// Good enough for budgeting, but whitespace-heavy text, code or digits can cost up to a token per
+/// byte, so it may undercount; [`ChunkOptions::default`] relies on a strict bound instead.
+pub fn estimate_tokens(text: &str) -> usize {
+	let (ascii, other): (usize, usize) =
+		text.chars().fold((0, 0), |(a, o), c| if c.is_ascii() { (a + 1, o) } else { (a, o + 1) });
+
+	ascii.div_ceil(3) + other
+}
+
+// The embedding models' tokenizer, or the byte length, which no byte-level BPE encoding exceeds.
+#[cfg(feature = "tokenizer")]
+fn bound_tokens(text: &str) -> usize {
+	crate::tokenizer::count_tokens(&Model::TextEmbedding3Small, text)
+}
+#[cfg(not(feature = "tokenizer"))]
+fn bound_tokens(text: &str) -> usize {
+	text.len()
+}
+
+/// Split `text` into chunks of at most `options.max_tokens` tokens.
+///
+/// Chunks break on whitespace where possible, words longer than the limit are split by characters.
+pub fn chunk_text(text: &str, options: &ChunkOptions) -> Vec<TextChunk> {
+	let max = options.max_tokens.max(1);
+	// `(start, end, tokens)` of each word, including its trailing whitespace.
+	let mut pieces = Vec::<(usize, usize, usize)>::new();
+	let mut start = 0;
+
+	for word in text.split_inclusive(char::is_whitespace) {
+		let tokens = (options.count_tokens)(word);
+
+		if tokens <= max {
+			pieces.push((start, start + word.len(), tokens));
+		} else {
+			// End offset of every character, so cuts always land on a char boundary.
+			let ends =
+				word.char_indices().map(|(i, c)| start + i + c.len_utf8()).collect::<Vec<_>>();
+			let mut sub_start = start;
+			let mut from = 0;
+
+			while from < ends.len() {
+				let fits = |k: usize| (options.count_tokens)(&text[sub_start..ends[k]]) <= max;
+				// Gallop past the cut, then bisect, so each piece only costs about its own length.
+				let (mut lo, mut step) = (from, 1);
+
+				while lo + step < ends.len() && fits(lo + step) {
+					lo += step;
+					step *= 2;
+				}
+
+				let mut hi = (lo + step).min(ends.len());
+
+				while hi - lo > 1 {
+					let mid = (lo + hi) / 2;
+
+					if fits(mid) { lo = mid } else { hi = mid }
+				}
+
+				pieces.push((
+					sub_start,
+					ends[lo],
+					(options.count_tokens)(&text[sub_start..ends[lo]]),
+				));
+
+				sub_start = ends[lo];
+				from = lo + 1;
+			}
+		}
+
+		start += word.len();
+	}
+
+	let mut chunks = Vec::new();
+	let mut i = 0;
+
+	while i < pieces.len() {
+		let mut j = i;
+		let mut tokens = 0;
+
+		while j < pieces.len() && (j == i || tokens + pieces[j].2 <= max) {
+			tokens += pieces[j].2;
+			j += 1;
+		}
+
+		let (start, end) = (pieces[i].0, pieces[j - 1].1);
+
+		chunks.push(TextChunk { text: text[start..end].into(), start, end });
+
+		if j == pieces.len() {
+			break;
+		}
+
+		// Step back over trailing pieces to build the overlap, always making progress.
+		let mut k = j;
+		let mut overlap = 0;
+
+		while k > i + 1 && overlap + pieces[k - 1].2 <= options.overlap_tokens {
+			overlap += pieces[k - 1].2;
+			k -= 1;
+		}
+
+		i = k;
+	}
+
+	chunks
+}
+
+/// Shorten a `text-embedding-3-*` vector to `dimensions` and L2-normalize it again.
+///
+/// Equivalent to requesting the embedding with `dimensions` set.
+///
+/// <https://platform.openai.com/docs/guides/embeddings#use-cases>
+pub fn truncate_embedding(embedding: &[f32], dimensions: usize) -> Vec<f32> {
+	let mut truncated = embedding[..dimensions.min(embedding.len())].to_vec();
+	let norm = truncated.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+	if norm > 0. {
+		truncated.iter_mut().for_each(|v| *v /= norm);
+	}
+
+	truncated
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Serialize)]
 pub struct EmbeddingRequest {
@@ -74,6 +371,12 @@ pub struct EmbeddingObject {
 	// Can be ignored.
 	// pub object: ConstEmbedding,
 }
+impl EmbeddingObject {
+	/// Truncate the embedding in place, see [`truncate_embedding`].
+	pub fn truncate(&mut self, dimensions: usize) {
+		self.embedding = truncate_embedding(&self.embedding, dimensions);
+	}
+}
 
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize)]
@@ -81,3 +384,52 @@ pub struct EmbeddingUsage {
 	pub prompt_tokens: u32,
 	pub total_tokens: u32,
 }
+
+#[test]
+fn chunk_text_should_work() {
+	let text = "foo bar baz qux";
+	let options = ChunkOptions::default().max_tokens(2).count_tokens(|_| 1);
+	let chunks = chunk_text(text, &options);
+
+	assert_eq!(chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), ["foo bar ", "baz qux"]);
+	assert!(chunks.iter().all(|c| text[c.start..c.end] == c.text));
+
+	let chunks = chunk_text(text, &options.clone().overlap_tokens(1));
+
+	assert_eq!(
+		chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+		["foo bar ", "bar baz ", "baz qux"]
+	);
+
+	let chunks = chunk_text("foobarbaz", &options.max_tokens(3).count_tokens(str::len));
+
+	assert_eq!(chunks.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), ["foo", "bar", "baz"]);
+}
+
+#[test]
+fn chunk_text_should_split_long_words_without_recounting() {
+	// std
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static COUNTED: AtomicUsize = AtomicUsize::new(0);
+
+	fn count(text: &str) -> usize {
+		COUNTED.fetch_add(text.len(), Ordering::Relaxed);
+
+		text.len()
+	}
+
+	let text = "x".repeat(100_000);
+	let chunks = chunk_text(&text, &ChunkOptions::default().max_tokens(1_000).count_tokens(count));
+
+	assert_eq!(chunks.len(), 100);
+	assert!(chunks.iter().all(|c| c.text.len() == 1_000));
+	// Recounting the growing slice per character would take about 50 MB.
+	assert!(COUNTED.load(Ordering::Relaxed) < 20 * text.len());
+}
+
+#[test]
+fn truncate_embedding_should_work() {
+	assert_eq!(truncate_embedding(&[3., 4., 12.], 2), [0.6, 0.8]);
+	assert_eq!(truncate_embedding(&[0., 0.], 4), [0., 0.]);
+}
@@ -2,6 +2,10 @@
 //!
 //! <https://platform.openai.com/docs/api-reference/files>
 
+// crates.io
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_util::bytes::Bytes;
 // self
 use crate::_prelude::*;
 
@@ -16,8 +20,22 @@ where
 		name: &str,
 		content: Vec<u8>,
 		purpose: Purpose,
+		expires_after: Option<ExpiresAfter>,
 	) -> impl Send + Future<Output = Result<FileObject>> {
 		async move {
+			let mut text = vec![(Cow::Borrowed("purpose"), Cow::Borrowed(purpose.as_str()))];
+
+			if let Some(expires_after) = expires_after {
+				text.push((
+					Cow::Borrowed("expires_after[anchor]"),
+					Cow::Borrowed(expires_after.anchor.as_str()),
+				));
+				text.push((
+					Cow::Borrowed("expires_after[seconds]"),
+					Cow::Owned(expires_after.seconds.to_string()),
+				));
+			}
+
 			let resp = self
 				.post_multipart(
 					"/files",
@@ -27,7 +45,7 @@ where
 							Cow::Owned(content),
 							Some(name.into()),
 						)],
-						text: vec![(Cow::Borrowed("purpose"), Cow::Borrowed(purpose.as_str()))],
+						text,
 					},
 				)
 				.await?;
@@ -38,10 +56,105 @@ where
 		}
 	}
 
+	/// List files, one page at a time.
+	fn list_files(
+		&self,
+		purpose: Option<Purpose>,
+		after: Option<&str>,
+		limit: Option<u32>,
+		order: Option<Order>,
+	) -> impl Send + Future<Output = Result<ListObject<FileObject>>> {
+		async move {
+			let query = serde_urlencoded::to_string([
+				("purpose", purpose.map(|p| p.as_str().to_owned())),
+				("after", after.map(Into::into)),
+				("limit", limit.map(|l| l.to_string())),
+				("order", order.map(|o| o.as_str().to_owned())),
+			])
+			.expect("string pairs must encode; qed");
+			let endpoint =
+				if query.is_empty() { "/files".into() } else { format!("/files?{query}") };
+			let resp = self.get(&endpoint).await?;
+
+			tracing::debug!("{resp}");
+
+			Ok(serde_json::from_str::<ApiResult<ListObject<FileObject>>>(&resp)?.as_result()?)
+		}
+	}
+
+	/// List all files, fetching further pages on demand.
+	fn list_files_stream(
+		&self,
+		purpose: Option<Purpose>,
+		limit: Option<u32>,
+		order: Option<Order>,
+	) -> impl Send + Stream<Item = Result<FileObject>> {
+		stream::try_unfold(Some(None::<String>), move |after| {
+			let purpose = purpose.clone();
+			let order = order.clone();
+
+			async move {
+				let Some(after) = after else { return Ok::<_, Error>(None) };
+				let page = self.list_files(purpose, after.as_deref(), limit, order).await?;
+				let next = if page.has_more { page.last_id.map(Some) } else { None };
+
+				Ok(Some((stream::iter(page.data.into_iter().map(Ok)), next)))
+			}
+		})
+		.try_flatten()
+	}
+
+	/// Delete a file by its ID.
+	fn delete_file(&self, file_id: &str) -> impl Send + Future<Output = Result<DeletionObject>> {
+		async move {
+			let resp = self.delete(&format!("/files/{file_id}")).await?;
+
+			tracing::debug!("{resp}");
+
+			Ok(serde_json::from_str::<ApiResult<DeletionObject>>(&resp)?.as_result()?)
+		}
+	}
+
+	/// Retrieve a file metadata by its ID.
+	fn retrieve_file(&self, file_id: &str) -> impl Send + Future<Output = Result<FileObject>> {
+		async move {
+			let resp = self.get(&format!("/files/{file_id}")).await?;
+
+			tracing::debug!("{resp}");
+
+			Ok(serde_json::from_str::<ApiResult<FileObject>>(&resp)?.as_result()?)
+		}
+	}
+
+	/// Retrieve a file content by its ID as raw bytes.
+	fn retrieve_file_bytes(&self, file_id: &str) -> impl Send + Future<Output = Result<Vec<u8>>> {
+		async move { Ok(self.get_bytes(&format!("/files/{file_id}/content")).await?.into()) }
+	}
+
+	/// Retrieve a file content by its ID as a byte stream, without buffering it in memory.
+	fn retrieve_file_content_stream(
+		&self,
+		file_id: &str,
+	) -> impl Send + Future<Output = Result<EventStream<Bytes>>> {
+		async move { self.get_bytes_stream(&format!("/files/{file_id}/content")).await }
+	}
+
+	/// Stream a file content by its ID into `writer`, returning the number of bytes written.
+	fn download_file<W>(
+		&self,
+		file_id: &str,
+		mut writer: W,
+	) -> impl Send + Future<Output = Result<u64>>
+	where
+		W: Send + Unpin + AsyncWrite,
+	{
+		async move { write_stream(self.retrieve_file_content_stream(file_id).await?, &mut writer).await }
+	}
+
 	/// Retrieve a file content by its ID.
 	fn retrieve_file_content(&self, file_id: &str) -> impl Send + Future<Output = Result<String>> {
 		async move {
-			let resp = self.get(&format!("/files/{file_id}")).await?;
+			let resp = self.get(&format!("/files/{file_id}/content")).await?;
 
 			tracing::debug!("{resp}");
 
@@ -51,6 +164,42 @@ where
 }
 impl<T> ApiFile for T where T: ApiBase {}
 
+/// Drains `stream` into `writer`, returning the number of bytes written.
+pub(crate) async fn write_stream<W>(mut stream: EventStream<Bytes>, writer: &mut W) -> Result<u64>
+where
+	W: Unpin + AsyncWrite,
+{
+	let mut written = 0;
+
+	while let Some(chunk) = stream.next().await {
+		let chunk = chunk?;
+
+		writer.write_all(&chunk).await?;
+
+		written += chunk.len() as u64;
+	}
+
+	writer.flush().await?;
+
+	Ok(written)
+}
+
+/// Expiration policy of an uploaded file.
+#[derive(Clone, Debug)]
+pub struct ExpiresAfter {
+	/// Timestamp the expiration is relative to.
+	pub anchor: ExpiresAfterAnchor,
+	/// Seconds after the anchor until the file is deleted, between one hour and 30 days.
+	pub seconds: u32,
+}
+
+impl_serializable_enum! {
+	ExpiresAfterAnchor {
+		#[default]
+		CreatedAt => "created_at",
+	}
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Deserialize)]
 pub struct FileObject {
@@ -99,3 +248,25 @@ impl<'de> Deserialize<'de> for StatusFallback {
 		}
 	}
 }
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn list_files_should_encode_query() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse};
+
+	let api = MockApi::new();
+	let page = serde_json::json!({ "data": [], "object": "list", "has_more": false });
+
+	api.respond(Method::GET, "/files?after=a+b%26limit%3D1&limit=2", MockResponse::json(&page));
+	api.respond(Method::GET, "/files", MockResponse::json(&page));
+
+	api.list_files(None, Some("a b&limit=1"), Some(2), None).await.unwrap();
+	api.list_files(None, None, None, None).await.unwrap();
+
+	let endpoints = api.requests().into_iter().map(|r| r.endpoint).collect::<Vec<_>>();
+
+	assert_eq!(endpoints, ["/files?after=a+b%26limit%3D1&limit=2", "/files"]);
+}
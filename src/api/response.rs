@@ -30,6 +30,9 @@ where
 		async {
 			// Ensure stream is disabled for non-streaming.
 			request.stream = None;
+			request.stream_options = None;
+
+			request.include_logprobs_if_requested();
 
 			let resp = self.post_json("/responses", request).await?;
 
@@ -52,8 +55,182 @@ where
 			// Ensure stream is enabled for streaming.
 			request.stream = Some(true);
 
+			request.include_logprobs_if_requested();
+
 			self.sse("/responses", request, options).await
 		}
 	}
+
+	/// Answer the MCP approval requests of `response` and create the follow-up response.
+	///
+	/// `approve` is called for every request in output order. The answers are sent as the input
+	/// of `request`, continuing from `response`. Returns `None` without sending anything if
+	/// `response` has no pending approval request.
+	fn approve_mcp_requests<F, Fut>(
+		&self,
+		response: &ResponseObject,
+		mut request: ResponseRequest,
+		approve: F,
+	) -> impl Send + Future<Output = Result<Option<ResponseObject>>>
+	where
+		F: Send + FnMut(McpApprovalRequest) -> Fut,
+		Fut: Send + Future<Output = McpApproval>,
+	{
+		let approval_requests = response.mcp_approval_requests().cloned().collect::<Vec<_>>();
+		let previous_response_id = response.id.clone();
+
+		async move {
+			if approval_requests.is_empty() {
+				return Ok(None);
+			}
+
+			request.input = Either::B(mcp_approval_responses(approval_requests, approve).await);
+			request.previous_response_id = Some(previous_response_id);
+
+			self.create_response(request).await.map(Some)
+		}
+	}
+
+	/// Cancel a background response by its ID.
+	fn cancel_response(&self, id: &str) -> impl Send + Future<Output = Result<ResponseObject>> {
+		async move {
+			let resp = self.post_json(&format!("/responses/{id}/cancel"), Map::new()).await?;
+
+			tracing::debug!("{resp}");
+
+			Ok(serde_json::from_str::<ApiResult<ResponseObject>>(&resp)?.as_result()?)
+		}
+	}
 }
 impl<T> ApiResponse for T where T: ApiBase {}
+
+/// Answer to an [`McpApprovalRequest`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct McpApproval {
+	/// Whether the MCP tool call may run.
+	pub approved: bool,
+	/// Reason of the decision, shown to the model.
+	pub reason: Option<String>,
+}
+impl McpApproval {
+	/// Lets the tool call run.
+	pub fn approve() -> Self {
+		Self { approved: true, reason: None }
+	}
+
+	/// Rejects the tool call for `reason`.
+	pub fn deny(reason: impl Into<String>) -> Self {
+		Self { approved: false, reason: Some(reason.into()) }
+	}
+}
+impl From<bool> for McpApproval {
+	fn from(approved: bool) -> Self {
+		Self { approved, reason: None }
+	}
+}
+
+/// Ask `approve` about every request and turn its answers into `mcp_approval_response` input
+/// items.
+///
+/// Use it to answer requests collected from a stream, e.g. from `response.output_item.done`
+/// events, before sending the follow-up request yourself.
+pub async fn mcp_approval_responses<I, F, Fut>(requests: I, mut approve: F) -> Vec<ResponseInput>
+where
+	I: IntoIterator<Item = McpApprovalRequest>,
+	F: FnMut(McpApprovalRequest) -> Fut,
+	Fut: Future<Output = McpApproval>,
+{
+	let mut inputs = Vec::new();
+
+	for request in requests {
+		let approval_request_id = request.id.clone();
+		let McpApproval { approved, reason } = approve(request).await;
+
+		tracing::debug!("MCP approval request {approval_request_id} approved: {approved}");
+
+		inputs.push(ResponseInput::Item(ResponseInputItem::McpApprovalResponse {
+			approval_request_id,
+			approved,
+			id: None,
+			reason,
+		}));
+	}
+
+	inputs
+}
+
+#[cfg(all(test, feature = "test-util"))]
+#[tokio::test]
+async fn approve_mcp_requests_should_work() {
+	// crates.io
+	use reqwest::Method;
+	// self
+	use crate::http::{MockApi, MockResponse};
+
+	let api = MockApi::new();
+	let response = |id: &str, output: Value| {
+		serde_json::from_value::<ResponseObject>(serde_json::json!({
+			"id": id,
+			"created_at": 0,
+			"metadata": {},
+			"model": "gpt-4o",
+			"object": "response",
+			"output": output,
+			"parallel_tool_calls": true,
+			"status": "completed",
+			"text": { "format": { "type": "text" } },
+			"tool_choice": "auto",
+			"tools": [],
+		}))
+		.unwrap()
+	};
+	let request = |id: &str, name: &str| {
+		serde_json::json!({
+			"type": "mcp_approval_request",
+			"arguments": "{}",
+			"id": id,
+			"name": name,
+			"server_label": "s",
+		})
+	};
+	let pending = response("r1", serde_json::json!([request("a", "read"), request("b", "drop")]));
+
+	api.respond(
+		Method::POST,
+		"/responses",
+		MockResponse::json(&response("r2", Value::Array(Vec::new()))),
+	);
+
+	let approve = |r: McpApprovalRequest| async move {
+		if r.name == "read" { McpApproval::approve() } else { McpApproval::deny("destructive") }
+	};
+	let followed = api
+		.approve_mcp_requests(&pending, ResponseRequest::default(), approve)
+		.await
+		.unwrap()
+		.unwrap();
+
+	assert_eq!(followed.id, "r2");
+
+	let body = api.requests()[0].body.clone().unwrap();
+
+	assert_eq!(body["previous_response_id"], "r1");
+	assert_eq!(
+		body["input"],
+		serde_json::json!([
+			{ "type": "mcp_approval_response", "approval_request_id": "a", "approved": true },
+			{
+				"type": "mcp_approval_response",
+				"approval_request_id": "b",
+				"approved": false,
+				"reason": "destructive",
+			},
+		])
+	);
+	assert!(
+		api.approve_mcp_requests(&followed, ResponseRequest::default(), approve)
+			.await
+			.unwrap()
+			.is_none()
+	);
+}
@@ -4,37 +4,51 @@
 
 #![allow(missing_docs)]
 
+// std
+use std::collections::HashMap;
 // self
 use super::r#type::*;
 use crate::_prelude::*;
 
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ResponseRequest {
 	pub input: Either<String, Vec<ResponseInput>>,
 	pub model: Model,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub background: Option<bool>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub conversation: Option<Either<String, Conversation>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub include: Option<Vec<Include>>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub instructions: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub max_output_tokens: Option<u32>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_tool_calls: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub metadata: Option<Map>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub parallel_tool_calls: Option<bool>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub previous_response_id: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub prompt: Option<Prompt>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub prompt_cache_key: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub reasoning: Option<Reasoning>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub safety_identifier: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub service_tier: Option<ServiceTier>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub store: Option<bool>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub stream: Option<bool>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stream_options: Option<ResponseStreamOptions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub temperature: Option<f32>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub text: Option<Text>,
@@ -43,6 +57,8 @@ pub struct ResponseRequest {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub tools: Option<Vec<Tool>>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub top_logprobs: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub top_p: Option<f32>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub truncation: Option<Truncation>,
@@ -50,15 +66,33 @@ pub struct ResponseRequest {
 	pub user: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+impl ResponseRequest {
+	/// Adds the output text logprobs include whenever `top_logprobs` is set, since the API only
+	/// returns logprobs on output text when that include is requested.
+	pub(crate) fn include_logprobs_if_requested(&mut self) {
+		if self.top_logprobs.is_none() {
+			return;
+		}
+
+		let include = self.include.get_or_insert_with(Vec::new);
+
+		if !include.contains(&Include::MessageOutputTextLogprobs) {
+			include.push(Include::MessageOutputTextLogprobs);
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ResponseInput {
-	Message(ResponseMessage<Either<String, Vec<ResponseMessageInputContent>>>),
+	// Try the tagged items first, otherwise a typed message item would be swallowed by the plain
+	// message variant and lose its `status` on deserialization.
 	Item(ResponseInputItem),
+	Message(ResponseMessage<Either<String, Vec<ResponseMessageInputContent>>>),
 	ItemReference { id: String },
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResponseMessageInputContent {
 	InputText {
@@ -81,7 +115,7 @@ pub enum ResponseMessageInputContent {
 	},
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResponseInputItem {
 	Message(Either<ResponseInputMessage, ResponseOutputMessage>),
@@ -128,9 +162,20 @@ pub enum ResponseInputItem {
 		reason: Option<String>,
 	},
 	McpCall(McpCall),
+	ShellCall(ShellCall),
+	ShellCallOutput(ShellCallOutput),
+	ApplyPatchCall(ApplyPatchCall),
+	ApplyPatchCallOutput(ApplyPatchCallOutput),
+	CustomToolCall(CustomToolCall),
+	CustomToolCallOutput {
+		call_id: String,
+		output: String,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		id: Option<String>,
+	},
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ComputerScreenshot {
 	pub r#type: ConstComputerScreenshot,
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -143,7 +188,7 @@ impl_const_str! {
 	ComputerScreenshot => "computer_screenshot",
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AcknowledgedSafetyCheck {
 	pub id: String,
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -152,7 +197,7 @@ pub struct AcknowledgedSafetyCheck {
 	pub message: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseInputMessage {
 	#[serde(flatten)]
 	pub message: ResponseMessage<Vec<ResponseMessageInputContent>>,
@@ -160,13 +205,32 @@ pub struct ResponseInputMessage {
 	pub status: Option<Status3>,
 }
 
-impl_serializable_enum! {
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResponseStreamOptions {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub include_obfuscation: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Prompt {
+	pub id: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub variables: Option<HashMap<String, Either<String, ResponseMessageInputContent>>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+}
+
+impl_serializable_deserializable_enum! {
+	#[other]
 	Include {
 		FileSearchCallResults => "file_search_call.results",
 		MessageInputImageImageUrl => "message.input_image.image_url",
 		ComputerCallOutputOutputImageUrl => "computer_call_output.output.image_url",
 		ReasoningEncryptedContent => "reasoning.encrypted_content",
 		CodeInterpreterCallOutputs => "code_interpreter_call.outputs",
+		MessageOutputTextLogprobs => "message.output_text.logprobs",
+		WebSearchCallResults => "web_search_call.results",
+		WebSearchCallActionSources => "web_search_call.action.sources",
 	}
 }
 
@@ -335,7 +399,22 @@ fn serialization_should_work() {
 			}),
 			ResponseInput::Item(ResponseInputItem::WebSearchCall(WebSearchCall {
 				id: "foo".into(),
-				status: "foo".into(),
+				status: WebSearchCallStatus::Completed,
+				action: Some(WebSearchAction::Search {
+					query: "foo".into(),
+					sources: Some(vec![WebSearchSource {
+						r#type: Default::default(),
+						url: "https://foo.bar".into(),
+					}]),
+				}),
+			})),
+			ResponseInput::Item(ResponseInputItem::WebSearchCall(WebSearchCall {
+				id: "foo".into(),
+				status: WebSearchCallStatus::Other("foo".into()),
+				action: Some(WebSearchAction::FindInPage {
+					pattern: "foo".into(),
+					url: "https://foo.bar".into(),
+				}),
 			})),
 			ResponseInput::Item(ResponseInputItem::FunctionCall(FunctionCall {
 				arguments: serde_json::json!({"foo":"bar"}),
@@ -353,19 +432,20 @@ fn serialization_should_work() {
 			ResponseInput::Item(ResponseInputItem::Reasoning(ReasoningItem {
 				id: "foo".into(),
 				summary: vec![SummaryText { text: "foo".into(), r#type: Default::default() }],
+				content: Some(vec![ReasoningContent::ReasoningText { text: "foo".into() }]),
 				encrypted_content: Some("foo".into()),
 				status: Some(Status3::Completed),
 			})),
 			ResponseInput::Item(ResponseInputItem::ImageGenerationCall(ImageGenerationCall {
 				id: "foo".into(),
 				result: Some("foo".into()),
-				status: "foo".into(),
+				status: ImageGenerationCallStatus::Generating,
 			})),
 			ResponseInput::Item(ResponseInputItem::CodeInterpreterCall(CodeInterpreterCall {
 				code: "foo('bar')".into(),
 				id: "foo".into(),
 				results: vec![CodeInterpreterCallOutput::Logs { logs: "foo".into() }],
-				status: "foo".into(),
+				status: CodeInterpreterCallStatus::Interpreting,
 				container_id: Some("foo".into()),
 			})),
 			ResponseInput::Item(ResponseInputItem::LocalShellCall(LocalShellCall {
@@ -379,7 +459,7 @@ fn serialization_should_work() {
 				},
 				call_id: "foo".into(),
 				id: "foo".into(),
-				status: "running".into(),
+				status: LocalShellCallStatus::InProgress,
 			})),
 			ResponseInput::Item(ResponseInputItem::LocalShellCallOutput {
 				id: "foo".into(),
@@ -417,28 +497,98 @@ fn serialization_should_work() {
 				error: None,
 				output: Some(r#"{"foo":"bar"}"#.into()),
 			})),
+			ResponseInput::Item(ResponseInputItem::ShellCall(ShellCall {
+				action: ShellCallAction {
+					commands: vec!["ls -la".into()],
+					max_output_length: Some(4096),
+					timeout_ms: Some(5000),
+				},
+				call_id: "foo".into(),
+				status: Status3::Completed,
+				id: Some("foo".into()),
+			})),
+			ResponseInput::Item(ResponseInputItem::ShellCallOutput(ShellCallOutput {
+				call_id: "foo".into(),
+				output: vec![ShellCallOutputContent {
+					outcome: ShellCallOutcome::Exit { exit_code: 0 },
+					stderr: "".into(),
+					stdout: "foo".into(),
+				}],
+				id: Some("foo".into()),
+				max_output_length: Some(4096),
+			})),
+			ResponseInput::Item(ResponseInputItem::ApplyPatchCall(ApplyPatchCall {
+				call_id: "foo".into(),
+				operation: ApplyPatchOperation::UpdateFile {
+					diff: "@@\n-foo\n+bar".into(),
+					path: "foo.txt".into(),
+				},
+				status: Status3::Completed,
+				id: Some("foo".into()),
+			})),
+			ResponseInput::Item(ResponseInputItem::ApplyPatchCallOutput(ApplyPatchCallOutput {
+				call_id: "foo".into(),
+				status: ApplyPatchCallOutputStatus::Completed,
+				id: Some("foo".into()),
+				output: Some("foo".into()),
+			})),
+			ResponseInput::Item(ResponseInputItem::CustomToolCall(CustomToolCall {
+				call_id: "foo".into(),
+				input: "foo".into(),
+				name: "foo".into(),
+				id: Some("foo".into()),
+			})),
+			ResponseInput::Item(ResponseInputItem::CustomToolCallOutput {
+				call_id: "foo".into(),
+				output: "foo".into(),
+				id: Some("foo".into()),
+			}),
 		]),
 		model: Model::Gpt4o,
 		background: Some(true),
+		conversation: Some(Either::B(Conversation { id: "foo".into() })),
 		include: Some(vec![
 			Include::FileSearchCallResults,
 			Include::MessageInputImageImageUrl,
 			Include::ComputerCallOutputOutputImageUrl,
 			Include::ReasoningEncryptedContent,
 			Include::CodeInterpreterCallOutputs,
+			Include::MessageOutputTextLogprobs,
+			Include::WebSearchCallResults,
+			Include::WebSearchCallActionSources,
+			Include::Other("foo.bar".into()),
 		]),
 		instructions: Some("foo".into()),
 		max_output_tokens: Some(2048),
+		max_tool_calls: Some(8),
 		metadata: Some(Map::from_iter([("foo".into(), "bar".into())])),
 		parallel_tool_calls: Some(true),
 		previous_response_id: Some("foo".into()),
+		prompt: Some(Prompt {
+			id: "foo".into(),
+			variables: Some(HashMap::from_iter([
+				("foo".into(), Either::A("bar".into())),
+				(
+					"baz".into(),
+					Either::B(ResponseMessageInputContent::InputFile {
+						file_data: None,
+						file_id: Some("foo".into()),
+						filename: None,
+					}),
+				),
+			])),
+			version: Some("1".into()),
+		}),
+		prompt_cache_key: Some("foo".into()),
 		reasoning: Some(Reasoning {
 			effort: Some(ReasoningEffort::High),
 			summary: Some(Summary::Detailed),
 		}),
+		safety_identifier: Some("foo".into()),
 		service_tier: Some(ServiceTier::Flex),
 		store: Some(true),
 		stream: Some(false),
+		stream_options: Some(ResponseStreamOptions { include_obfuscation: Some(false) }),
 		temperature: Some(0.7),
 		text: Some(Text {
 			format: Some(ResponseTextFormat::JsonSchema {
@@ -447,6 +597,7 @@ fn serialization_should_work() {
 				description: Some("foo".into()),
 				strict: Some(true),
 			}),
+			verbosity: Some(Verbosity::Low),
 		}),
 		tool_choice: Some(ToolChoice::HostedTool { r#type: HostedTool::FileSearch }),
 		tools: Some(vec![
@@ -468,6 +619,11 @@ fn serialization_should_work() {
 					score_threshold: Some(0.8),
 				}),
 			},
+			Tool::WebSearch {
+				filters: Some(WebSearchFilters { allowed_domains: Some(vec!["foo.bar".into()]) }),
+				search_context_size: Some(SearchContextSize::Medium),
+				user_location: None,
+			},
 			Tool::WebSearchPreview {
 				search_context_size: Some(SearchContextSize::High),
 				user_location: Some(Location {
@@ -481,14 +637,27 @@ fn serialization_should_work() {
 			Tool::ComputerUsePreview {
 				display_height: 1440,
 				display_width: 2560,
-				environment: "foo".into(),
+				environment: ComputerEnvironment::Browser,
 			},
 			Tool::Mcp {
 				server_label: "foo".into(),
-				server_url: "https://foo.bar/baz".into(),
+				server_url: Some("https://foo.bar/baz".into()),
+				connector_id: None,
 				allowed_tools: Some(Either::A(vec!["foo".into(), "bar".into()])),
+				authorization: None,
 				headers: Some(serde_json::json!({"foo":"bar"})),
 				require_approval: Some(Either::B(McpApprovalSetting::Always)),
+				server_description: Some("foo".into()),
+			},
+			Tool::Mcp {
+				server_label: "bar".into(),
+				server_url: None,
+				connector_id: Some(McpConnector::GoogleDrive),
+				allowed_tools: None,
+				authorization: Some("foo".into()),
+				headers: None,
+				require_approval: None,
+				server_description: None,
 			},
 			Tool::CodeInterpreter {
 				container: Either::B(CodeInterpreterContainer {
@@ -517,7 +686,18 @@ fn serialization_should_work() {
 				size: Some(ImageSize::W1024H1536),
 			},
 			Tool::LocalShell,
+			Tool::Shell,
+			Tool::ApplyPatch,
+			Tool::Custom {
+				name: "foo".into(),
+				description: Some("foo".into()),
+				format: Some(CustomToolFormat::Grammar {
+					definition: "start: \"foo\"".into(),
+					syntax: GrammarSyntax::Lark,
+				}),
+			},
 		]),
+		top_logprobs: Some(5),
 		top_p: Some(0.95),
 		truncation: Some(Truncation::Auto),
 		user: Some("foo".into()),
@@ -525,4 +705,30 @@ fn serialization_should_work() {
 	let serialized = serde_json::to_string(&req).expect("serialization must succeed; qed");
 
 	println!("{serialized}");
+
+	let deserialized = serde_json::from_str::<ResponseRequest>(&serialized)
+		.expect("deserialization must succeed; qed");
+	let reserialized =
+		serde_json::to_string(&deserialized).expect("serialization must succeed; qed");
+
+	// Compare as values since `HashMap` iteration order is not stable.
+	assert_eq!(
+		serde_json::from_str::<Value>(&reserialized).expect("deserialization must succeed; qed"),
+		serde_json::from_str::<Value>(&serialized).expect("deserialization must succeed; qed"),
+	);
+}
+
+#[test]
+fn mcp_tool_should_work() {
+	let tool = serde_json::from_str::<Tool>(
+		r#"{"type":"mcp","server_label":"foo","connector_id":"connector_foo","authorization":"sk-foo"}"#,
+	)
+	.expect("deserialization must succeed; qed");
+
+	assert!(!format!("{tool:?}").contains("sk-foo"));
+	assert!(serde_json::to_string(&tool).unwrap().contains(r#""authorization":"sk-foo""#));
+
+	let Tool::Mcp { connector_id, .. } = tool else { panic!("tool must be an MCP tool") };
+
+	assert_eq!(connector_id, Some(McpConnector::Other("connector_foo".into())));
 }
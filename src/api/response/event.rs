@@ -7,7 +7,7 @@ use super::{object::*, r#type::*};
 use crate::_prelude::*;
 
 /// All possible events from the OpenAI Response API stream.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ResponseEvent {
 	#[serde(rename = "response.created")]
@@ -92,62 +92,78 @@ pub enum ResponseEvent {
 	ReasoningDelta(ResponseReasoningDeltaEvent),
 	#[serde(rename = "response.reasoning.done")]
 	ReasoningDone(ResponseReasoningDoneEvent),
+	#[serde(rename = "response.reasoning_text.delta")]
+	ReasoningTextDelta(ResponseReasoningTextDeltaEvent),
+	#[serde(rename = "response.reasoning_text.done")]
+	ReasoningTextDone(ResponseReasoningTextDoneEvent),
 	#[serde(rename = "response.reasoning_summary.delta")]
 	ReasoningSummaryDelta(ResponseReasoningSummaryDeltaEvent),
 	#[serde(rename = "response.reasoning_summary.done")]
 	ReasoningSummaryDone(ResponseReasoningSummaryDoneEvent),
+	#[serde(rename = "response.custom_tool_call_input.delta")]
+	CustomToolCallInputDelta(ResponseCustomToolCallInputDeltaEvent),
+	#[serde(rename = "response.custom_tool_call_input.done")]
+	CustomToolCallInputDone(ResponseCustomToolCallInputDoneEvent),
+	#[serde(rename = "response.shell_call_command.delta")]
+	ShellCallCommandDelta(ResponseShellCallCommandDeltaEvent),
+	#[serde(rename = "response.shell_call_command.done")]
+	ShellCallCommandDone(ResponseShellCallCommandDoneEvent),
+	#[serde(rename = "response.apply_patch_call_operation_diff.delta")]
+	ApplyPatchCallOperationDiffDelta(ResponseApplyPatchCallOperationDiffDeltaEvent),
+	#[serde(rename = "response.apply_patch_call_operation_diff.done")]
+	ApplyPatchCallOperationDiffDone(ResponseApplyPatchCallOperationDiffDoneEvent),
 	#[serde(rename = "error")]
 	Error(ErrorEvent),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EventBase {
 	pub sequence_number: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseCreatedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: ResponseObject,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: ResponseObject,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: ResponseObject,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFailedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: ResponseObject,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseIncompleteEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: ResponseObject,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseQueuedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 	pub response: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseOutputItemAddedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -155,7 +171,7 @@ pub struct ResponseOutputItemAddedEvent {
 	pub item: ResponseOutput,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseOutputItemDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -163,7 +179,7 @@ pub struct ResponseOutputItemDoneEvent {
 	pub item: ResponseOutput,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseContentPartAddedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -173,7 +189,7 @@ pub struct ResponseContentPartAddedEvent {
 	pub part: ResponseMessageOutputContent,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseContentPartDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -183,7 +199,7 @@ pub struct ResponseContentPartDoneEvent {
 	pub part: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseOutputTextDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -191,9 +207,10 @@ pub struct ResponseOutputTextDeltaEvent {
 	pub output_index: u32,
 	pub content_index: u32,
 	pub delta: String,
+	pub logprobs: Option<Vec<Logprobs>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseOutputTextDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -201,9 +218,10 @@ pub struct ResponseOutputTextDoneEvent {
 	pub output_index: u32,
 	pub content_index: u32,
 	pub text: String,
+	pub logprobs: Option<Vec<Logprobs>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseRefusalDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -213,7 +231,7 @@ pub struct ResponseRefusalDeltaEvent {
 	pub delta: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseRefusalDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -223,7 +241,7 @@ pub struct ResponseRefusalDoneEvent {
 	pub refusal: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFunctionCallArgumentsDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -232,7 +250,7 @@ pub struct ResponseFunctionCallArgumentsDeltaEvent {
 	pub delta: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFunctionCallArgumentsDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -241,7 +259,7 @@ pub struct ResponseFunctionCallArgumentsDoneEvent {
 	pub arguments: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFileSearchCallInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -249,7 +267,7 @@ pub struct ResponseFileSearchCallInProgressEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFileSearchCallSearchingEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -257,7 +275,7 @@ pub struct ResponseFileSearchCallSearchingEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseFileSearchCallCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -265,7 +283,7 @@ pub struct ResponseFileSearchCallCompletedEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseWebSearchCallInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -273,7 +291,7 @@ pub struct ResponseWebSearchCallInProgressEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseWebSearchCallSearchingEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -281,7 +299,7 @@ pub struct ResponseWebSearchCallSearchingEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseWebSearchCallCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -289,7 +307,7 @@ pub struct ResponseWebSearchCallCompletedEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryPartAddedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -299,7 +317,7 @@ pub struct ResponseReasoningSummaryPartAddedEvent {
 	pub part: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryPartDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -309,7 +327,7 @@ pub struct ResponseReasoningSummaryPartDoneEvent {
 	pub part: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryTextDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -319,7 +337,7 @@ pub struct ResponseReasoningSummaryTextDeltaEvent {
 	pub delta: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryTextDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -329,7 +347,7 @@ pub struct ResponseReasoningSummaryTextDoneEvent {
 	pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseImageGenerationCallCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -337,7 +355,7 @@ pub struct ResponseImageGenerationCallCompletedEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseImageGenerationCallGeneratingEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -345,7 +363,7 @@ pub struct ResponseImageGenerationCallGeneratingEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseImageGenerationCallInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -353,7 +371,7 @@ pub struct ResponseImageGenerationCallInProgressEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseImageGenerationCallPartialImageEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -363,7 +381,7 @@ pub struct ResponseImageGenerationCallPartialImageEvent {
 	pub partial_image_b64: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpCallArgumentsDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -372,7 +390,7 @@ pub struct ResponseMcpCallArgumentsDeltaEvent {
 	pub delta: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpCallArgumentsDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -381,19 +399,19 @@ pub struct ResponseMcpCallArgumentsDoneEvent {
 	pub arguments: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpCallCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpCallFailedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpCallInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -401,25 +419,25 @@ pub struct ResponseMcpCallInProgressEvent {
 	pub output_index: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpListToolsCompletedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpListToolsFailedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseMcpListToolsInProgressEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseOutputTextAnnotationAddedEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -430,7 +448,7 @@ pub struct ResponseOutputTextAnnotationAddedEvent {
 	pub annotation: Annotation,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -440,7 +458,7 @@ pub struct ResponseReasoningDeltaEvent {
 	pub delta: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -450,7 +468,27 @@ pub struct ResponseReasoningDoneEvent {
 	pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseReasoningTextDeltaEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub content_index: u32,
+	pub delta: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseReasoningTextDoneEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub content_index: u32,
+	pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryDeltaEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -460,7 +498,7 @@ pub struct ResponseReasoningSummaryDeltaEvent {
 	pub delta: Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ResponseReasoningSummaryDoneEvent {
 	#[serde(flatten)]
 	pub base: EventBase,
@@ -470,7 +508,63 @@ pub struct ResponseReasoningSummaryDoneEvent {
 	pub text: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseCustomToolCallInputDeltaEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub delta: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseCustomToolCallInputDoneEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub input: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseShellCallCommandDeltaEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub command_index: u32,
+	pub delta: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseShellCallCommandDoneEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub command_index: u32,
+	pub command: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseApplyPatchCallOperationDiffDeltaEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub delta: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseApplyPatchCallOperationDiffDoneEvent {
+	#[serde(flatten)]
+	pub base: EventBase,
+	pub item_id: String,
+	pub output_index: u32,
+	pub diff: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorEvent {
 	#[serde(flatten)]
 	pub error: ErrorBase,
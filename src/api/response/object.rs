@@ -8,15 +8,17 @@
 use super::r#type::*;
 use crate::_prelude::*;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseObject {
 	pub background: Option<bool>,
+	pub conversation: Option<Conversation>,
 	pub created_at: u64,
 	pub error: Option<ResponseError>,
 	pub id: String,
 	pub incomplete_details: Option<IncompleteDetails>,
 	pub instructions: Option<String>,
 	pub max_output_tokens: Option<u32>,
+	pub max_tool_calls: Option<u32>,
 	pub metadata: Map,
 	pub model: Model,
 	// Can be ignored.
@@ -25,31 +27,63 @@ pub struct ResponseObject {
 	pub output_text: Option<String>,
 	pub parallel_tool_calls: bool,
 	pub previous_response_id: Option<String>,
+	pub prompt_cache_key: Option<String>,
 	pub reasoning: Option<Reasoning>,
+	pub safety_identifier: Option<String>,
 	pub service_tier: Option<ServiceTier>,
 	pub status: ResponseStatus,
 	pub temperature: Option<f32>,
 	pub text: Text,
 	pub tool_choice: ToolChoice,
 	pub tools: Vec<Tool>,
+	pub top_logprobs: Option<u8>,
 	pub top_p: Option<f32>,
 	pub truncation: Option<Truncation>,
 	pub usage: Option<ResponseUsage>,
 	pub user: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+impl ResponseObject {
+	/// Iterates over the logprobs of every output text part, in output order.
+	///
+	/// Empty unless the request set `top_logprobs` or included `message.output_text.logprobs`.
+	pub fn logprobs(&self) -> impl Iterator<Item = &Logprobs> {
+		self.output
+			.iter()
+			.filter_map(|o| match o {
+				ResponseOutput::Message(m) => Some(&m.message.content),
+				_ => None,
+			})
+			.flatten()
+			.filter_map(|c| match c {
+				ResponseMessageOutputContent::OutputText { logprobs, .. } => logprobs.as_ref(),
+				_ => None,
+			})
+			.flatten()
+	}
+
+	/// Iterates over the MCP tool calls awaiting approval, in output order.
+	pub fn mcp_approval_requests(&self) -> impl Iterator<Item = &McpApprovalRequest> {
+		self.output.iter().filter_map(|o| match o {
+			ResponseOutput::McpApprovalRequest(r) => Some(r),
+			_ => None,
+		})
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseError {
 	pub code: String,
 	pub message: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct IncompleteDetails {
 	pub reason: String,
 }
 
-impl_deserializable_enum! {
+impl_serializable_deserializable_enum! {
+	#[other]
 	ResponseStatus {
 		Completed => "completed",
 		Failed => "failed",
@@ -60,7 +94,7 @@ impl_deserializable_enum! {
 	}
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResponseOutput {
 	Message(ResponseOutputMessage),
@@ -75,9 +109,14 @@ pub enum ResponseOutput {
 	McpCall(McpCall),
 	McpListTools(McpListTools),
 	McpApprovalRequest(McpApprovalRequest),
+	ShellCall(ShellCall),
+	ShellCallOutput(ShellCallOutput),
+	ApplyPatchCall(ApplyPatchCall),
+	ApplyPatchCallOutput(ApplyPatchCallOutput),
+	CustomToolCall(CustomToolCall),
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseUsage {
 	pub input_tokens: u32,
 	pub input_tokens_details: ResponseInputTokensDetails,
@@ -86,12 +125,12 @@ pub struct ResponseUsage {
 	pub total_tokens: u32,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseInputTokensDetails {
 	pub cached_tokens: u32,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseOutputTokensDetails {
 	pub reasoning_tokens: u32,
 }
@@ -100,6 +139,9 @@ pub struct ResponseOutputTokensDetails {
 fn deserialization_should_work() {
 	let resp = r#"{
 	"background": true,
+	"conversation": {
+		"id": "foo"
+	},
 	"created_at": 1609459200,
 	"error": {
 		"code": "foo",
@@ -111,6 +153,7 @@ fn deserialization_should_work() {
 	},
 	"instructions": "foo",
 	"max_output_tokens": 2048,
+	"max_tool_calls": 8,
 	"metadata": {"foo": "bar"},
 	"model": "gpt-4o",
 	"output": [
@@ -125,7 +168,21 @@ fn deserialization_should_work() {
 						}
 					],
 					"text": "foo",
-					"type": "output_text"
+					"type": "output_text",
+					"logprobs": [
+						{
+							"bytes": [102, 111, 111],
+							"logprob": -0.1,
+							"token": "foo",
+							"top_logprobs": [
+								{
+									"bytes": [102, 111, 111],
+									"logprob": -0.1,
+									"token": "foo"
+								}
+							]
+						}
+					]
 				}
 			],
 			"role": "assistant",
@@ -159,7 +216,11 @@ fn deserialization_should_work() {
 		{
 			"id": "foo",
 			"status": "completed",
-			"type": "web_search_call"
+			"type": "web_search_call",
+			"action": {
+				"type": "open_page",
+				"url": "https://foo.bar"
+			}
 		},
 		{
 			"id": "foo",
@@ -170,6 +231,12 @@ fn deserialization_should_work() {
 				}
 			],
 			"type": "reasoning",
+			"content": [
+				{
+					"text": "foo",
+					"type": "reasoning_text"
+				}
+			],
 			"encrypted_content": "foo",
 			"status": "completed"
 		},
@@ -236,15 +303,68 @@ fn deserialization_should_work() {
 			"name": "foo",
 			"server_label": "foo",
 			"type": "mcp_approval_request"
+		},
+		{
+			"action": {
+				"commands": ["ls -la"],
+				"max_output_length": 4096,
+				"timeout_ms": 5000
+			},
+			"call_id": "foo",
+			"status": "completed",
+			"type": "shell_call",
+			"id": "foo"
+		},
+		{
+			"call_id": "foo",
+			"output": [
+				{
+					"outcome": {
+						"exit_code": 0,
+						"type": "exit"
+					},
+					"stderr": "",
+					"stdout": "foo"
+				}
+			],
+			"type": "shell_call_output",
+			"id": "foo"
+		},
+		{
+			"call_id": "foo",
+			"operation": {
+				"diff": "@@\n-foo\n+bar",
+				"path": "foo.txt",
+				"type": "update_file"
+			},
+			"status": "completed",
+			"type": "apply_patch_call",
+			"id": "foo"
+		},
+		{
+			"call_id": "foo",
+			"status": "failed",
+			"type": "apply_patch_call_output",
+			"id": "foo",
+			"output": "foo"
+		},
+		{
+			"call_id": "foo",
+			"input": "foo",
+			"name": "foo",
+			"type": "custom_tool_call",
+			"id": "foo"
 		}
 	],
 	"output_text": "foo",
 	"parallel_tool_calls": true,
 	"previous_response_id": "foo",
+	"prompt_cache_key": "foo",
 	"reasoning": {
 		"effort": "high",
 		"summary": "detailed"
 	},
+	"safety_identifier": "foo",
 	"service_tier": "flex",
 	"status": "completed",
 	"temperature": 0.7,
@@ -259,7 +379,8 @@ fn deserialization_should_work() {
 			"type": "json_schema",
 			"description": "foo",
 			"strict": true
-		}
+		},
+		"verbosity": "medium"
 	},
 	"tool_choice": "auto",
 	"tools": [
@@ -275,6 +396,7 @@ fn deserialization_should_work() {
 			"description": "foo"
 		}
 	],
+	"top_logprobs": 5,
 	"top_p": 0.95,
 	"truncation": "auto",
 	"usage": {
@@ -294,4 +416,14 @@ fn deserialization_should_work() {
 		.expect("comprehensive deserialization must succeed; qed");
 
 	println!("{deserialized:?}");
+
+	let logprobs = deserialized.logprobs().collect::<Vec<_>>();
+
+	assert_eq!(logprobs.len(), 1);
+	assert_eq!(logprobs[0].logprob.token, "foo");
+	assert_eq!(logprobs[0].top_logprobs.len(), 1);
+
+	let serialized = serde_json::to_string(&deserialized).expect("serialization must succeed; qed");
+
+	serde_json::from_str::<ResponseObject>(&serialized).expect("deserialization must succeed; qed");
 }
@@ -11,6 +11,11 @@ pub struct ResponseMessage<T> {
 	pub role: Role,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Conversation {
+	pub id: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResponseOutputMessage {
 	#[serde(flatten)]
@@ -62,6 +67,7 @@ pub struct Refusal {
 }
 
 impl_serializable_deserializable_enum! {
+	#[other]
 	Status3 {
 		InProgress => "in_progress",
 		Completed => "completed",
@@ -92,6 +98,7 @@ pub struct FileSearchCall {
 }
 
 impl_serializable_deserializable_enum! {
+	#[other]
 	FileSearchToolCallStatus {
 		InProgress => "in_progress",
 		Searching => "searching",
@@ -185,7 +192,47 @@ pub struct PendingSafetyCheck {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WebSearchCall {
 	pub id: String,
-	pub status: String,
+	pub status: WebSearchCallStatus,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub action: Option<WebSearchAction>,
+}
+
+impl_serializable_deserializable_enum! {
+	#[other]
+	WebSearchCallStatus {
+		InProgress => "in_progress",
+		Searching => "searching",
+		Completed => "completed",
+		Failed => "failed",
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSearchAction {
+	Search {
+		query: String,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		sources: Option<Vec<WebSearchSource>>,
+	},
+	OpenPage {
+		#[serde(skip_serializing_if = "Option::is_none")]
+		url: Option<String>,
+	},
+	FindInPage {
+		pattern: String,
+		url: String,
+	},
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebSearchSource {
+	pub r#type: ConstUrl,
+	pub url: String,
+}
+
+impl_const_str! {
+	Url => "url",
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -199,11 +246,22 @@ pub struct FunctionCall {
 	pub status: Option<Status3>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomToolCall {
+	pub call_id: String,
+	pub input: String,
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ReasoningItem {
 	pub id: String,
 	pub summary: Vec<SummaryText>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub content: Option<Vec<ReasoningContent>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub encrypted_content: Option<String>,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub status: Option<Status3>,
@@ -219,12 +277,28 @@ impl_const_str! {
 	SummaryText  => "summary_text",
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReasoningContent {
+	ReasoningText { text: String },
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ImageGenerationCall {
 	pub id: String,
 	// This field requires explicit null serialization.
 	pub result: Option<String>,
-	pub status: String,
+	pub status: ImageGenerationCallStatus,
+}
+
+impl_serializable_deserializable_enum! {
+	#[other]
+	ImageGenerationCallStatus {
+		InProgress => "in_progress",
+		Completed => "completed",
+		Generating => "generating",
+		Failed => "failed",
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -232,11 +306,22 @@ pub struct CodeInterpreterCall {
 	pub code: String,
 	pub id: String,
 	pub results: Vec<CodeInterpreterCallOutput>,
-	pub status: String,
+	pub status: CodeInterpreterCallStatus,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub container_id: Option<String>,
 }
 
+impl_serializable_deserializable_enum! {
+	#[other]
+	CodeInterpreterCallStatus {
+		InProgress => "in_progress",
+		Completed => "completed",
+		Incomplete => "incomplete",
+		Interpreting => "interpreting",
+		Failed => "failed",
+	}
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum CodeInterpreterCallOutput {
@@ -255,7 +340,16 @@ pub struct LocalShellCall {
 	pub action: ShellAction,
 	pub call_id: String,
 	pub id: String,
-	pub status: String,
+	pub status: LocalShellCallStatus,
+}
+
+impl_serializable_deserializable_enum! {
+	#[other]
+	LocalShellCallStatus {
+		InProgress => "in_progress",
+		Completed => "completed",
+		Incomplete => "incomplete",
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -275,6 +369,83 @@ impl_const_str! {
 	Exec  => "exec",
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellCall {
+	pub action: ShellCallAction,
+	pub call_id: String,
+	pub status: Status3,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellCallAction {
+	pub commands: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_output_length: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub timeout_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellCallOutput {
+	pub call_id: String,
+	pub output: Vec<ShellCallOutputContent>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_output_length: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShellCallOutputContent {
+	pub outcome: ShellCallOutcome,
+	pub stderr: String,
+	pub stdout: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShellCallOutcome {
+	Exit { exit_code: i32 },
+	Timeout,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApplyPatchCall {
+	pub call_id: String,
+	pub operation: ApplyPatchOperation,
+	pub status: Status3,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApplyPatchOperation {
+	CreateFile { diff: String, path: String },
+	UpdateFile { diff: String, path: String },
+	DeleteFile { path: String },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApplyPatchCallOutput {
+	pub call_id: String,
+	pub status: ApplyPatchCallOutputStatus,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<String>,
+}
+
+impl_serializable_deserializable_enum! {
+	#[other]
+	ApplyPatchCallOutputStatus {
+		Completed => "completed",
+		Failed => "failed",
+	}
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct McpListTools {
 	pub id: String,
@@ -334,6 +505,16 @@ impl_serializable_deserializable_enum! {
 pub struct Text {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub format: Option<ResponseTextFormat>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub verbosity: Option<Verbosity>,
+}
+
+impl_serializable_deserializable_enum! {
+	Verbosity {
+		Low => "low",
+		Medium => "medium",
+		High => "high",
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -357,6 +538,11 @@ pub enum ToolChoice {
 	Mode(ToolChoiceMode),
 	HostedTool { r#type: HostedTool },
 	FunctionTool { name: String, r#type: ConstFunction },
+	CustomTool { name: String, r#type: ConstCustom },
+}
+
+impl_const_str! {
+	Custom => "custom",
 }
 
 impl_serializable_deserializable_enum! {
@@ -370,12 +556,15 @@ impl_serializable_deserializable_enum! {
 impl_serializable_deserializable_enum! {
 	HostedTool {
 		FileSearch => "file_search",
+		WebSearch => "web_search",
 		WebSearchPreview => "web_search_preview",
 		WebSearchPreview20250311 => "web_search_preview_2025_03_11",
 		ComputerUsePreview => "computer_use_preview",
 		CodeInterpreter => "code_interpreter",
 		Mcp => "mcp",
 		ImageGeneration => "image_generation",
+		Shell => "shell",
+		ApplyPatch => "apply_patch",
 	}
 }
 
@@ -398,6 +587,14 @@ pub enum Tool {
 		#[serde(skip_serializing_if = "Option::is_none")]
 		ranking_options: Option<RankingOptions>,
 	},
+	WebSearch {
+		#[serde(skip_serializing_if = "Option::is_none")]
+		filters: Option<WebSearchFilters>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		search_context_size: Option<SearchContextSize>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		user_location: Option<Location>,
+	},
 	WebSearchPreview {
 		#[serde(skip_serializing_if = "Option::is_none")]
 		search_context_size: Option<SearchContextSize>,
@@ -407,17 +604,25 @@ pub enum Tool {
 	ComputerUsePreview {
 		display_height: u32,
 		display_width: u32,
-		environment: String,
+		environment: ComputerEnvironment,
 	},
 	Mcp {
 		server_label: String,
-		server_url: String,
+		// Either `server_url` or `connector_id` must be set.
+		#[serde(skip_serializing_if = "Option::is_none")]
+		server_url: Option<String>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		connector_id: Option<McpConnector>,
 		#[serde(skip_serializing_if = "Option::is_none")]
 		allowed_tools: Option<Either<Vec<String>, McpFilter>>,
 		#[serde(skip_serializing_if = "Option::is_none")]
+		authorization: Option<Secret>,
+		#[serde(skip_serializing_if = "Option::is_none")]
 		headers: Option<Value>,
 		#[serde(skip_serializing_if = "Option::is_none")]
 		require_approval: Option<Either<McpApprovalFilter, McpApprovalSetting>>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		server_description: Option<String>,
 	},
 	CodeInterpreter {
 		container: Either<String, CodeInterpreterContainer>,
@@ -443,6 +648,29 @@ pub enum Tool {
 		size: Option<ImageSize>,
 	},
 	LocalShell,
+	Shell,
+	ApplyPatch,
+	Custom {
+		name: String,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		description: Option<String>,
+		#[serde(skip_serializing_if = "Option::is_none")]
+		format: Option<CustomToolFormat>,
+	},
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CustomToolFormat {
+	Text,
+	Grammar { definition: String, syntax: GrammarSyntax },
+}
+
+impl_serializable_deserializable_enum! {
+	GrammarSyntax {
+		Lark => "lark",
+		Regex => "regex",
+	}
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -480,6 +708,12 @@ pub struct RankingOptions {
 	pub score_threshold: Option<f32>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebSearchFilters {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_domains: Option<Vec<String>>,
+}
+
 impl_serializable_deserializable_enum! {
 	SearchContextSize {
 		Low => "low",
@@ -506,6 +740,16 @@ impl_const_str! {
 	Approximate  => "approximate",
 }
 
+impl_serializable_deserializable_enum! {
+	ComputerEnvironment {
+		Browser => "browser",
+		Mac => "mac",
+		Windows => "windows",
+		Ubuntu => "ubuntu",
+		Linux => "linux",
+	}
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct McpFilter {
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -520,6 +764,20 @@ pub struct McpApprovalFilter {
 	pub never: Option<McpFilter>,
 }
 
+impl_serializable_deserializable_enum! {
+	#[other]
+	McpConnector {
+		Dropbox => "connector_dropbox",
+		Gmail => "connector_gmail",
+		GoogleCalendar => "connector_googlecalendar",
+		GoogleDrive => "connector_googledrive",
+		MicrosoftTeams => "connector_microsoftteams",
+		OutlookCalendar => "connector_outlookcalendar",
+		OutlookEmail => "connector_outlookemail",
+		SharePoint => "connector_sharepoint",
+	}
+}
+
 impl_serializable_deserializable_enum! {
 	McpApprovalSetting {
 		Always => "always",
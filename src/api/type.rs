@@ -17,12 +17,17 @@ impl_serializable_deserializable_enum! {
 		User => "user",
 		Assistant => "assistant",
 		System => "system",
-		Developer => "developer"
+		Developer => "developer",
+		Tool => "tool",
+		// Legacy role of function call results.
+		Function => "function",
 	}
 }
 
 impl_serializable_deserializable_enum! {
 	ReasoningEffort {
+		None => "none",
+		Minimal => "minimal",
 		Low => "low",
 		Medium => "medium",
 		High => "high"
@@ -30,14 +35,17 @@ impl_serializable_deserializable_enum! {
 }
 
 impl_serializable_deserializable_enum! {
+	#[other]
 	ServiceTier {
 		Auto => "auto",
 		Default => "default",
 		Flex => "flex",
+		Priority => "priority",
+		Scale => "scale",
 	}
 }
 
-impl_serializable_enum! {
+impl_serializable_deserializable_enum! {
 	ImageDetail {
 		High => "high",
 		Low => "low",
@@ -57,6 +65,38 @@ impl_serializable_enum! {
 	}
 }
 
+impl_serializable_enum! {
+	Order {
+		Asc => "asc",
+		#[default]
+		Desc => "desc",
+	}
+}
+
+/// A page of a paginated list endpoint.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ListObject<T> {
+	/// Items of this page.
+	pub data: Vec<T>,
+	/// ID of the first item in this page.
+	pub first_id: Option<String>,
+	/// Whether more items are available after this page.
+	pub has_more: bool,
+	/// ID of the last item in this page, used as the cursor of the next page.
+	pub last_id: Option<String>,
+}
+
+/// Result of deleting an object.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeletionObject {
+	/// Whether the object was deleted.
+	pub deleted: bool,
+	/// ID of the deleted object.
+	pub id: String,
+	/// Type of the deleted object, e.g. `file`.
+	pub object: String,
+}
+
 /// Represents either a successful API response or an error response.
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
@@ -85,6 +125,45 @@ pub enum Either<A, B> {
 	/// Second type variant.
 	B(B),
 }
+impl<A, B> Either<A, B> {
+	/// Returns the first type's value, if any.
+	pub fn as_a(&self) -> Option<&A> {
+		match self {
+			Self::A(a) => Some(a),
+			Self::B(_) => None,
+		}
+	}
+
+	/// Returns the second type's value, if any.
+	pub fn as_b(&self) -> Option<&B> {
+		match self {
+			Self::A(_) => None,
+			Self::B(b) => Some(b),
+		}
+	}
+
+	/// Maps the first type's value with `f`, leaving the second one untouched.
+	pub fn map_a<C, F>(self, f: F) -> Either<C, B>
+	where
+		F: FnOnce(A) -> C,
+	{
+		match self {
+			Self::A(a) => Either::A(f(a)),
+			Self::B(b) => Either::B(b),
+		}
+	}
+
+	/// Maps the second type's value with `f`, leaving the first one untouched.
+	pub fn map_b<C, F>(self, f: F) -> Either<A, C>
+	where
+		F: FnOnce(B) -> C,
+	{
+		match self {
+			Self::A(a) => Either::A(a),
+			Self::B(b) => Either::B(f(b)),
+		}
+	}
+}
 impl<A, B> Default for Either<A, B>
 where
 	A: Default,
@@ -94,6 +173,24 @@ where
 		Self::A(A::default())
 	}
 }
+impl<T> From<String> for Either<String, Vec<T>> {
+	fn from(text: String) -> Self {
+		Self::A(text)
+	}
+}
+impl<T> From<&str> for Either<String, Vec<T>> {
+	fn from(text: &str) -> Self {
+		Self::A(text.into())
+	}
+}
+impl<T> From<Vec<T>> for Either<String, Vec<T>> {
+	fn from(parts: Vec<T>) -> Self {
+		Self::B(parts)
+	}
+}
+
+/// Message content, either plain text or a list of parts, e.g. `"Hi".into()`.
+pub type Content<T> = Either<String, Vec<T>>;
 
 /// Wrapper structure for API error responses.
 #[derive(Clone, Debug, Deserialize)]
@@ -111,6 +208,36 @@ pub struct ApiError {
 	#[serde(flatten)]
 	pub base: ErrorBase,
 }
+impl ApiError {
+	/// Classifies the error by its code, falling back to its type.
+	pub fn kind(&self) -> ApiErrorKind {
+		let by_code = match self.base.code.as_ref().and_then(Either::as_a).map(String::as_str) {
+			Some("invalid_api_key") => ApiErrorKind::Authentication,
+			Some("insufficient_quota") => ApiErrorKind::InsufficientQuota,
+			Some("context_length_exceeded") => ApiErrorKind::ContextLengthExceeded,
+			Some("rate_limit_exceeded") => ApiErrorKind::RateLimit,
+			Some("model_not_found") => ApiErrorKind::NotFound,
+			Some("content_policy_violation" | "content_filter") => ApiErrorKind::ContentPolicy,
+			Some("server_is_overloaded" | "engine_overloaded") => ApiErrorKind::Overloaded,
+			_ => ApiErrorKind::Unknown,
+		};
+
+		if by_code != ApiErrorKind::Unknown {
+			return by_code;
+		}
+
+		match self.r#type.as_deref() {
+			Some("invalid_request_error") => ApiErrorKind::InvalidRequest,
+			Some("authentication_error") => ApiErrorKind::Authentication,
+			Some("permission_error" | "permission_denied") => ApiErrorKind::PermissionDenied,
+			Some("not_found_error") => ApiErrorKind::NotFound,
+			Some("rate_limit_error" | "requests" | "tokens") => ApiErrorKind::RateLimit,
+			Some("insufficient_quota") => ApiErrorKind::InsufficientQuota,
+			Some("server_error" | "api_error") => ApiErrorKind::Server,
+			_ => ApiErrorKind::Unknown,
+		}
+	}
+}
 impl Display for ApiError {
 	fn fmt(&self, f: &mut Formatter) -> FmtResult {
 		Debug::fmt(self, f)
@@ -118,13 +245,42 @@ impl Display for ApiError {
 }
 impl ErrorT for ApiError {}
 
+/// Failure class of an [`ApiError`], see [`ApiError::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiErrorKind {
+	/// The request is malformed or has invalid parameters.
+	InvalidRequest,
+	/// The API key is missing, invalid or revoked.
+	Authentication,
+	/// The key has no access to the resource.
+	PermissionDenied,
+	/// The model or resource does not exist.
+	NotFound,
+	/// Too many requests or tokens in a time window.
+	RateLimit,
+	/// The account ran out of credits or hit its spend limit.
+	InsufficientQuota,
+	/// The input does not fit the context window of the model.
+	ContextLengthExceeded,
+	/// The input or output was rejected by the content policy.
+	ContentPolicy,
+	/// The server is overloaded, the request may be retried later.
+	Overloaded,
+	/// The server failed to process the request.
+	Server,
+	/// The error is of no known class.
+	Unknown,
+}
+
 /// Contains the basic error information common to all API errors.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ErrorBase {
 	/// Human-readable description of the error.
 	pub message: String,
-	/// Optional error code identifying the specific error type.
-	pub code: Option<u32>,
+	/// Optional error code identifying the specific error, e.g. `context_length_exceeded`.
+	///
+	/// Some compatible servers send numeric codes.
+	pub code: Option<Either<String, u32>>,
 	/// Optional parameter name that caused the error.
 	pub param: Option<String>,
 }
@@ -134,6 +290,7 @@ pub struct ErrorBase {
 pub struct Logprobs {
 	#[serde(flatten)]
 	pub logprob: Logprob,
+	#[serde(default)]
 	pub top_logprobs: Vec<Logprob>,
 }
 
@@ -144,3 +301,75 @@ pub struct Logprob {
 	pub logprob: f32,
 	pub token: String,
 }
+impl Logprob {
+	/// Linear probability of the token, i.e. `exp(logprob)`.
+	pub fn probability(&self) -> f32 {
+		self.logprob.exp()
+	}
+}
+
+#[test]
+fn api_error_kind_should_work() {
+	let kind = |json: &str| serde_json::from_str::<ApiErrorWrapper>(json).unwrap().error.kind();
+
+	assert_eq!(
+		kind(
+			r#"{"error":{"message":"m","type":"invalid_request_error","param":"messages","code":"context_length_exceeded"}}"#
+		),
+		ApiErrorKind::ContextLengthExceeded
+	);
+	assert_eq!(
+		kind(
+			r#"{"error":{"message":"m","type":"invalid_request_error","param":null,"code":null}}"#
+		),
+		ApiErrorKind::InvalidRequest
+	);
+	assert_eq!(
+		kind(r#"{"error":{"message":"m","type":"insufficient_quota","param":null,"code":429}}"#),
+		ApiErrorKind::InsufficientQuota
+	);
+}
+
+#[test]
+fn service_tier_should_tolerate_unknown() {
+	for tier in [ServiceTier::Priority, ServiceTier::Scale, ServiceTier::Other("turbo".into())] {
+		let json = serde_json::to_string(&tier).unwrap();
+
+		assert_eq!(serde_json::from_str::<ServiceTier>(&json).unwrap(), tier);
+	}
+
+	assert_eq!(serde_json::to_string(&ServiceTier::Priority).unwrap(), "\"priority\"");
+}
+
+#[test]
+fn reasoning_effort_should_work() {
+	for (effort, json) in [
+		(ReasoningEffort::None, "\"none\""),
+		(ReasoningEffort::Minimal, "\"minimal\""),
+		(ReasoningEffort::Medium, "\"medium\""),
+	] {
+		assert_eq!(serde_json::to_string(&effort).unwrap(), json);
+		assert_eq!(serde_json::from_str::<ReasoningEffort>(json).unwrap(), effort);
+	}
+}
+
+#[test]
+fn role_should_work() {
+	for (role, json) in [(Role::Tool, "\"tool\""), (Role::Function, "\"function\"")] {
+		assert_eq!(serde_json::to_string(&role).unwrap(), json);
+		assert_eq!(serde_json::from_str::<Role>(json).unwrap(), role);
+	}
+}
+
+#[test]
+fn either_should_work() {
+	let text = Content::<u8>::from("foo");
+
+	assert_eq!(text.as_a().map(String::as_str), Some("foo"));
+	assert!(text.as_b().is_none());
+	assert_eq!(text.map_a(|t| t.len()).as_a(), Some(&3));
+
+	let parts = Content::from(vec![1, 2]);
+
+	assert_eq!(parts.map_b(|p| p.len()).as_b(), Some(&2));
+}
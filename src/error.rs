@@ -2,6 +2,8 @@
 
 // std
 use std::time::Duration;
+// self
+use crate::api::r#type::ApiErrorKind;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -10,6 +12,8 @@ pub enum Error {
 	#[error("{0}")]
 	Any(String),
 
+	#[error("environment variable {name}: {source}")]
+	Env { name: &'static str, source: std::env::VarError },
 	#[error(transparent)]
 	Io(#[from] std::io::Error),
 
@@ -19,11 +23,39 @@ pub enum Error {
 	Reqwest(#[from] reqwest::Error),
 	#[error(transparent)]
 	SerdeJson(#[from] serde_json::Error),
+	#[cfg(feature = "toml")]
+	#[error(transparent)]
+	Toml(#[from] toml::de::Error),
+
+	#[cfg(feature = "mcp")]
+	#[error(transparent)]
+	Mcp(Box<rmcp::RmcpError>),
 
 	#[error(transparent)]
 	Agent(#[from] AgentError),
 	#[error(transparent)]
 	Api(#[from] crate::api::r#type::ApiError),
+	#[error("HTTP {status}: {body}")]
+	Http {
+		status: reqwest::StatusCode,
+		headers: Box<reqwest::header::HeaderMap>,
+		body: String,
+		request_id: Option<String>,
+		/// The API error carried by `body`, if it is one.
+		api: Option<Box<crate::api::r#type::ApiError>>,
+	},
+	#[error("rate limited, retry after {retry_after:?}")]
+	RateLimited {
+		retry_after: Option<Duration>,
+		/// Limits reported by the `x-ratelimit-*` headers of the rejected response.
+		limit_info: Option<Box<crate::http::RateLimitInfo>>,
+	},
+	#[error("budget exceeded: {0:?}")]
+	BudgetExceeded(crate::http::BudgetUsage),
+	#[error("circuit open for {endpoint}")]
+	CircuitOpen { endpoint: String, retry_after: Option<Duration> },
+	#[error("cancelled")]
+	Cancelled,
 	#[error("timeout after {0:?}")]
 	Timeout(Duration),
 	#[error(transparent)]
@@ -36,18 +68,165 @@ impl Error {
 	{
 		Self::Any(any.into())
 	}
+
+	/// Whether the same request may succeed when sent again later.
+	///
+	/// True for rate limits, timeouts, open circuits, transport failures, and server side errors.
+	pub fn is_retryable(&self) -> bool {
+		match self {
+			Self::RateLimited { .. } | Self::CircuitOpen { .. } | Self::Timeout(_) => true,
+			Self::Reqwest(e) =>
+				e.is_timeout()
+					|| e.is_connect()
+					|| e.is_body() || e.status().is_some_and(retryable_status),
+			Self::Http { status, api, .. } => match api.as_ref().map(|e| e.kind()) {
+				Some(kind) if kind != ApiErrorKind::Unknown => retryable_kind(kind),
+				_ => retryable_status(*status),
+			},
+			Self::Api(e) => retryable_kind(e.kind()),
+			_ => self.is_stream_interrupted(),
+		}
+	}
+
+	/// Whether the request itself was rejected, so sending it again unchanged fails again.
+	pub fn is_client_error(&self) -> bool {
+		let client_status = |s: reqwest::StatusCode| s.is_client_error() && !retryable_status(s);
+
+		match self {
+			Self::Reqwest(e) => e.status().is_some_and(client_status),
+			Self::Http { status, api, .. } => match api.as_ref().map(|e| e.kind()) {
+				Some(kind) if kind != ApiErrorKind::Unknown => client_kind(kind),
+				_ => client_status(*status),
+			},
+			Self::Api(e) => client_kind(e.kind()),
+			_ => false,
+		}
+	}
+
+	/// Whether a response or event stream broke off while it was read.
+	pub fn is_stream_interrupted(&self) -> bool {
+		match self {
+			// Local file and process failures are io errors too, only count broken connections.
+			Self::Io(e) => matches!(
+				e.kind(),
+				std::io::ErrorKind::ConnectionReset
+					| std::io::ErrorKind::ConnectionAborted
+					| std::io::ErrorKind::BrokenPipe
+					| std::io::ErrorKind::UnexpectedEof
+			),
+			Self::Codec(tokio_util::codec::LinesCodecError::Io(_)) => true,
+			Self::Reqwest(e) => e.is_body() || e.is_decode(),
+			_ => false,
+		}
+	}
+
+	/// Failure class of an API error, `None` for other errors.
+	pub fn api_kind(&self) -> Option<ApiErrorKind> {
+		match self {
+			Self::Api(e) => Some(e.kind()),
+			Self::Http { api: Some(e), .. } => Some(e.kind()),
+			_ => None,
+		}
+	}
+}
+#[cfg(feature = "mcp")]
+impl From<rmcp::RmcpError> for Error {
+	fn from(e: rmcp::RmcpError) -> Self {
+		Self::Mcp(Box::new(e))
+	}
+}
+
+/// Timeouts, conflicts, rate limits and server errors.
+fn retryable_status(status: reqwest::StatusCode) -> bool {
+	matches!(status.as_u16(), 408 | 409 | 429) || status.is_server_error()
+}
+
+fn retryable_kind(kind: ApiErrorKind) -> bool {
+	matches!(kind, ApiErrorKind::RateLimit | ApiErrorKind::Overloaded | ApiErrorKind::Server)
+}
+
+fn client_kind(kind: ApiErrorKind) -> bool {
+	matches!(
+		kind,
+		ApiErrorKind::InvalidRequest
+			| ApiErrorKind::Authentication
+			| ApiErrorKind::PermissionDenied
+			| ApiErrorKind::NotFound
+			| ApiErrorKind::InsufficientQuota
+			| ApiErrorKind::ContextLengthExceeded
+			| ApiErrorKind::ContentPolicy
+	)
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum AgentError {
 	#[error("maximum steps {0} reached without final answer")]
 	MaxStepsExceeded(usize),
+	#[error("run ended without final answer: {}", .0.as_deref().unwrap_or("no error reported"))]
+	NoFinalAnswer(Option<String>),
+	#[error("guardrail rejected {target}: {reason}")]
+	GuardrailRejected { target: String, reason: String },
+	#[error("run exceeded its deadline of {0:?}")]
+	DeadlineExceeded(Duration),
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ToolError {
+	#[error("invalid arguments for tool '{tool}': {message}")]
+	InvalidArguments { tool: String, message: String },
+	#[error(
+		"arguments of tool '{tool}' violate its schema: {}",
+		errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+	)]
+	SchemaViolation { tool: String, errors: Vec<crate::tool::ArgumentError> },
+	#[error("path '{0}' is outside of the tool root")]
+	PathOutsideRoot(String),
 	#[error("tool '{0}' does not support streaming")]
 	StreamingNotSupported(String),
 	#[error("unknown tool: {0}")]
 	Unknown(String),
 }
+
+#[test]
+fn classification_should_work() {
+	// crates.io
+	use reqwest::StatusCode;
+
+	let http = |status| Error::Http {
+		status,
+		headers: Default::default(),
+		body: String::new(),
+		request_id: None,
+		api: None,
+	};
+	let api = |r#type: &str| {
+		Error::Api(
+			serde_json::from_value(serde_json::json!({ "message": "m", "type": r#type })).unwrap(),
+		)
+	};
+	let quota = Error::Http {
+		status: StatusCode::TOO_MANY_REQUESTS,
+		headers: Default::default(),
+		body: String::new(),
+		request_id: None,
+		api: serde_json::from_value(
+			serde_json::json!({ "message": "m", "type": "insufficient_quota" }),
+		)
+		.ok(),
+	};
+	let reset = Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+
+	assert!(Error::RateLimited { retry_after: None, limit_info: None }.is_retryable());
+	assert!(http(StatusCode::BAD_GATEWAY).is_retryable());
+	assert!(!http(StatusCode::BAD_GATEWAY).is_client_error());
+	assert!(http(StatusCode::UNAUTHORIZED).is_client_error());
+	assert!(!http(StatusCode::UNAUTHORIZED).is_retryable());
+	assert!(!http(StatusCode::TOO_MANY_REQUESTS).is_client_error());
+	assert!(api("server_error").is_retryable());
+	assert!(api("invalid_request_error").is_client_error());
+	assert!(!quota.is_retryable() && quota.is_client_error());
+	assert_eq!(quota.api_kind(), Some(ApiErrorKind::InsufficientQuota));
+	assert!(reset.is_stream_interrupted() && reset.is_retryable());
+	assert!(!Error::Io(std::io::Error::from(std::io::ErrorKind::NotFound)).is_retryable());
+	assert!(!Error::Cancelled.is_retryable());
+}
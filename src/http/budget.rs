@@ -0,0 +1,275 @@
+//! Token and spend budget guard.
+
+// std
+use std::{
+	ops::{Add, AddAssign},
+	sync::Mutex,
+};
+// self
+use crate::{
+	_prelude::*,
+	api::{chat::ChatUsage, response::ResponseUsage},
+};
+
+/// Limits enforced by [`Api::budget`], a request is rejected once any of them is reached.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+	/// Maximum number of input tokens.
+	pub max_input_tokens: Option<u64>,
+	/// Maximum number of output tokens.
+	pub max_output_tokens: Option<u64>,
+	/// Maximum number of input and output tokens combined.
+	pub max_total_tokens: Option<u64>,
+	/// Maximum spend, in the currency of `pricing`.
+	pub max_cost: Option<f64>,
+	/// Token prices used to compute the spend.
+	pub pricing: Option<Pricing>,
+}
+
+/// Token prices, per million tokens.
+///
+/// Built-in models come with their list prices, see [`Model::pricing`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+	/// Price of one million input tokens.
+	pub input: f64,
+	/// Price of one million cached input tokens, the input price if `None`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub cached_input: Option<f64>,
+	/// Price of one million output tokens, reasoning tokens included.
+	pub output: f64,
+}
+impl Pricing {
+	/// Prices `input` and `output` tokens without a cache discount.
+	pub const fn new(input: f64, output: f64) -> Self {
+		Self { input, cached_input: None, output }
+	}
+
+	/// Sets the price of cached input tokens.
+	pub const fn cached_input(mut self, cached_input: f64) -> Self {
+		self.cached_input = Some(cached_input);
+
+		self
+	}
+}
+
+/// Spend of one or more calls, split by kind of token.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cost {
+	/// Spend on uncached input tokens.
+	pub input: f64,
+	/// Spend on cached input tokens.
+	pub cached_input: f64,
+	/// Spend on output tokens, reasoning tokens excluded.
+	pub output: f64,
+	/// Spend on reasoning tokens.
+	pub reasoning: f64,
+}
+impl Cost {
+	/// Computes the spend of a chat completion.
+	pub fn from_chat(usage: &ChatUsage, pricing: &Pricing) -> Self {
+		Self::new(
+			pricing,
+			usage.prompt_tokens.into(),
+			usage.prompt_tokens_details.as_ref().map_or(0, |d| d.cached_tokens).into(),
+			usage.completion_tokens.into(),
+			usage.completion_tokens_details.as_ref().map_or(0, |d| d.reasoning_tokens).into(),
+		)
+	}
+
+	/// Computes the spend of a response.
+	pub fn from_response(usage: &ResponseUsage, pricing: &Pricing) -> Self {
+		Self::new(
+			pricing,
+			usage.input_tokens.into(),
+			usage.input_tokens_details.cached_tokens.into(),
+			usage.output_tokens.into(),
+			usage.output_tokens_details.reasoning_tokens.into(),
+		)
+	}
+
+	fn new(pricing: &Pricing, input: u64, cached: u64, output: u64, reasoning: u64) -> Self {
+		let cached = cached.min(input);
+		let reasoning = reasoning.min(output);
+		let price = |tokens: u64, price: f64| tokens as f64 * price / 1_000_000.;
+
+		Self {
+			input: price(input - cached, pricing.input),
+			cached_input: price(cached, pricing.cached_input.unwrap_or(pricing.input)),
+			output: price(output - reasoning, pricing.output),
+			reasoning: price(reasoning, pricing.output),
+		}
+	}
+
+	/// Spend on all tokens.
+	pub fn total(&self) -> f64 {
+		self.input + self.cached_input + self.output + self.reasoning
+	}
+}
+impl Add for Cost {
+	type Output = Self;
+
+	fn add(mut self, rhs: Self) -> Self {
+		self += rhs;
+
+		self
+	}
+}
+impl AddAssign for Cost {
+	fn add_assign(&mut self, rhs: Self) {
+		self.input += rhs.input;
+		self.cached_input += rhs.cached_input;
+		self.output += rhs.output;
+		self.reasoning += rhs.reasoning;
+	}
+}
+
+/// Usage accumulated against a [`Budget`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BudgetUsage {
+	/// Input tokens consumed.
+	pub input_tokens: u64,
+	/// Output tokens consumed.
+	pub output_tokens: u64,
+	/// Spend, zero unless [`Budget::pricing`] is set.
+	pub cost: f64,
+}
+impl BudgetUsage {
+	/// Input and output tokens combined.
+	pub fn total_tokens(&self) -> u64 {
+		self.input_tokens + self.output_tokens
+	}
+}
+
+#[derive(Debug)]
+pub(crate) struct BudgetGuard {
+	budget: Budget,
+	usage: Mutex<BudgetUsage>,
+}
+impl BudgetGuard {
+	pub(crate) fn new(budget: Budget) -> Self {
+		Self { budget, usage: Default::default() }
+	}
+
+	pub(crate) fn usage(&self) -> BudgetUsage {
+		self.usage.lock().expect("lock must succeed; qed").clone()
+	}
+
+	pub(crate) fn check(&self) -> Result<()> {
+		let usage = self.usage();
+		let b = &self.budget;
+		let exceeded = b.max_input_tokens.is_some_and(|m| usage.input_tokens >= m)
+			|| b.max_output_tokens.is_some_and(|m| usage.output_tokens >= m)
+			|| b.max_total_tokens.is_some_and(|m| usage.total_tokens() >= m)
+			|| b.max_cost.is_some_and(|m| usage.cost >= m);
+
+		if exceeded { Err(Error::BudgetExceeded(usage)) } else { Ok(()) }
+	}
+
+	pub(crate) fn record(&self, tokens: UsageTokens) {
+		let mut u = self.usage.lock().expect("lock must succeed; qed");
+
+		u.input_tokens += tokens.input;
+		u.output_tokens += tokens.output;
+
+		if let Some(p) = &self.budget.pricing {
+			u.cost += Cost::new(p, tokens.input, tokens.cached_input, tokens.output, 0).total();
+		}
+	}
+}
+
+/// Token counts of a `usage` object, see [`usage_from_json`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct UsageTokens {
+	pub(crate) input: u64,
+	/// Part of `input` served from the prompt cache.
+	pub(crate) cached_input: u64,
+	pub(crate) output: u64,
+}
+
+/// Extracts the token counts from the `usage` object of a response body or stream event, if
+/// there is one.
+///
+/// Understands chat (`prompt_tokens`/`completion_tokens`), responses
+/// (`input_tokens`/`output_tokens`, also nested under `response`) and embedding usage.
+pub(crate) fn usage_from_json(body: &str) -> Option<UsageTokens> {
+	// Skip parsing bodies which can not carry usage.
+	if !body.contains("\"usage\"") {
+		return None;
+	}
+
+	let v = serde_json::from_str::<Value>(body).ok()?;
+	let usage = v.get("usage").or_else(|| v.get("response")?.get("usage"))?;
+	let get = |keys: [&str; 2]| keys.iter().find_map(|k| usage.get(*k)?.as_u64()).unwrap_or(0);
+	let cached_input = ["input_tokens_details", "prompt_tokens_details"]
+		.iter()
+		.find_map(|k| usage.get(*k)?.get("cached_tokens")?.as_u64())
+		.unwrap_or(0);
+
+	Some(UsageTokens {
+		input: get(["input_tokens", "prompt_tokens"]),
+		cached_input,
+		output: get(["output_tokens", "completion_tokens"]),
+	})
+}
+
+#[test]
+fn budget_guard_should_work() {
+	let guard = BudgetGuard::new(Budget {
+		max_total_tokens: Some(40),
+		pricing: Some(Pricing::new(1., 2.)),
+		..Default::default()
+	});
+	let chat = r#"{"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}"#;
+	let response = r#"{"type":"response.completed","response":{"usage":{"input_tokens":10,"output_tokens":5}}}"#;
+	let tokens = UsageTokens { input: 10, cached_input: 0, output: 5 };
+
+	assert_eq!(usage_from_json(chat), Some(tokens));
+	assert_eq!(usage_from_json(response), Some(tokens));
+	assert_eq!(usage_from_json(r#"{"type":"response.output_text.delta","delta":"hi"}"#), None);
+
+	guard.record(tokens);
+	guard.record(tokens);
+
+	assert_eq!(guard.usage().total_tokens(), 30);
+	assert!((guard.usage().cost - 0.00004).abs() < f64::EPSILON);
+	assert!(guard.check().is_ok());
+
+	guard.record(UsageTokens { input: 10, cached_input: 0, output: 0 });
+
+	assert!(matches!(guard.check(), Err(Error::BudgetExceeded(_))));
+}
+
+#[test]
+fn budget_should_price_cached_input() {
+	let pricing = Pricing::new(2., 8.).cached_input(0.5);
+	let guard = BudgetGuard::new(Budget { pricing: Some(pricing.clone()), ..Default::default() });
+	let chat = r#"{"usage":{"prompt_tokens":1000000,"prompt_tokens_details":{"cached_tokens":400000},"completion_tokens":500000,"completion_tokens_details":{"reasoning_tokens":250000},"total_tokens":1500000}}"#;
+	let tokens = usage_from_json(chat).unwrap();
+
+	assert_eq!(tokens, UsageTokens { input: 1_000_000, cached_input: 400_000, output: 500_000 });
+
+	guard.record(tokens);
+
+	let usage = serde_json::from_str::<Value>(chat).unwrap()["usage"].clone();
+	let cost = Cost::from_chat(&serde_json::from_value(usage).unwrap(), &pricing);
+
+	assert!((guard.usage().cost - cost.total()).abs() < 1e-9);
+}
+
+#[test]
+fn cost_should_work() {
+	let pricing = Pricing::new(2., 8.).cached_input(0.5);
+	let usage = serde_json::from_value::<ResponseUsage>(serde_json::json!({
+		"input_tokens": 1_000_000,
+		"input_tokens_details": { "cached_tokens": 400_000 },
+		"output_tokens": 500_000,
+		"output_tokens_details": { "reasoning_tokens": 250_000 },
+		"total_tokens": 1_500_000,
+	}))
+	.unwrap();
+	let cost = Cost::from_response(&usage, &pricing);
+
+	assert_eq!(cost, Cost { input: 1.2, cached_input: 0.2, output: 2., reasoning: 2. });
+	assert!(((cost.clone() + cost).total() - 10.8).abs() < 1e-9);
+}
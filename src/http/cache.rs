@@ -0,0 +1,118 @@
+//! TTL cache for GET responses.
+
+// std
+use std::{
+	collections::HashMap,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+// self
+use crate::_prelude::*;
+
+/// `(uri, overriding key)` to `(cached at, body)`.
+type Entries = HashMap<(String, Option<Secret>), (Instant, String)>;
+
+/// Response cache policy, see [`Api::cache`].
+///
+/// Successful GET responses of `endpoints` are served from memory until `ttl` elapses. Any other
+/// request to the same top-level resource, e.g. `DELETE /files/{id}` for `/files`, evicts its
+/// entries.
+#[derive(Clone, Debug)]
+pub struct Cache {
+	/// How long a response stays fresh.
+	pub ttl: Duration,
+	/// Maximum number of cached responses.
+	pub max_entries: usize,
+	/// Path prefixes of the cached endpoints, e.g. `/models` for `/models/{id}` too.
+	///
+	/// Keep polled status endpoints such as `/batches` out, they would stay stale for `ttl`.
+	pub endpoints: Vec<String>,
+}
+impl Default for Cache {
+	fn default() -> Self {
+		Self {
+			ttl: Duration::from_secs(60),
+			max_entries: 256,
+			endpoints: vec!["/models".into(), "/files".into()],
+		}
+	}
+}
+
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+	policy: Cache,
+	entries: Mutex<Entries>,
+}
+impl ResponseCache {
+	pub(crate) fn new(policy: Cache) -> Self {
+		Self { policy, entries: Default::default() }
+	}
+
+	/// Whether GET responses of `endpoint` are cached.
+	pub(crate) fn caches(&self, endpoint: &str) -> bool {
+		self.policy.endpoints.iter().any(|prefix| {
+			endpoint
+				.strip_prefix(prefix.as_str())
+				.is_some_and(|r| r.is_empty() || r.starts_with(['/', '?']))
+		})
+	}
+
+	/// Returns the fresh response of `uri`, as seen with the overriding `key`.
+	pub(crate) fn get(&self, uri: &str, key: Option<&Secret>) -> Option<String> {
+		let entries = self.entries.lock().expect("lock must succeed; qed");
+		let (at, body) = entries.get(&(uri.into(), key.cloned()))?;
+
+		(at.elapsed() < self.policy.ttl).then(|| body.clone())
+	}
+
+	pub(crate) fn insert(&self, uri: &str, key: Option<&Secret>, body: String) {
+		let mut entries = self.entries.lock().expect("lock must succeed; qed");
+
+		if entries.len() >= self.policy.max_entries {
+			entries.retain(|_, (at, _)| at.elapsed() < self.policy.ttl);
+		}
+		if entries.len() >= self.policy.max_entries
+			&& let Some(oldest) =
+				entries.iter().min_by_key(|(_, (at, _))| *at).map(|(k, _)| k.clone())
+		{
+			entries.remove(&oldest);
+		}
+		if self.policy.max_entries > 0 {
+			entries.insert((uri.into(), key.cloned()), (Instant::now(), body));
+		}
+	}
+
+	/// Evicts the responses of the top-level resource `endpoint` belongs to.
+	pub(crate) fn invalidate(&self, base_uri: &str, endpoint: &str) {
+		let resource = endpoint.split(['/', '?']).find(|s| !s.is_empty()).unwrap_or_default();
+		let prefix = format!("{base_uri}/{resource}");
+
+		self.entries.lock().expect("lock must succeed; qed").retain(|(uri, _), _| {
+			!uri.strip_prefix(&prefix).is_some_and(|r| r.is_empty() || r.starts_with(['/', '?']))
+		});
+	}
+
+	pub(crate) fn clear(&self) {
+		self.entries.lock().expect("lock must succeed; qed").clear();
+	}
+}
+
+#[test]
+fn response_cache_should_work() {
+	let cache = ResponseCache::new(Cache { max_entries: 2, ..Default::default() });
+
+	cache.insert("x/files", None, "a".into());
+	cache.insert("x/files/1", None, "b".into());
+	cache.insert("x/filesystems", None, "c".into());
+
+	assert_eq!(cache.get("x/files", None), None);
+	assert_eq!(cache.get("x/files/1", None).as_deref(), Some("b"));
+	assert_eq!(cache.get("x/files/1", Some(&"k".into())), None);
+
+	cache.invalidate("x", "/files/1");
+
+	assert_eq!(cache.get("x/files/1", None), None);
+	assert_eq!(cache.get("x/filesystems", None).as_deref(), Some("c"));
+	assert!(cache.caches("/files") && cache.caches("/files/1") && cache.caches("/models?a=b"));
+	assert!(!cache.caches("/filesystems") && !cache.caches("/batches/1"));
+}
@@ -0,0 +1,128 @@
+//! Per-endpoint circuit breaker.
+
+// std
+use std::{
+	collections::HashMap,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+// self
+use crate::_prelude::*;
+
+/// Circuit breaker policy, see [`Api::circuit_breaker`].
+///
+/// After `failure_threshold` consecutive failures of an endpoint, calls to it fail fast with
+/// [`Error::CircuitOpen`] for `cooldown`. Then a single probe is let through: success closes the
+/// circuit, failure opens it again. A probe with no outcome after another `cooldown`, e.g. as its
+/// call was dropped, is replaced by a new one.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+	/// Consecutive failures which open the circuit.
+	pub failure_threshold: u32,
+	/// How long the circuit stays open before probing.
+	pub cooldown: Duration,
+}
+impl Default for CircuitBreaker {
+	fn default() -> Self {
+		Self { failure_threshold: 5, cooldown: Duration::from_secs(30) }
+	}
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+	failures: u32,
+	opened_at: Option<Instant>,
+	probing_since: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Circuits {
+	policy: CircuitBreaker,
+	states: Mutex<HashMap<String, CircuitState>>,
+}
+impl Circuits {
+	pub(crate) fn new(policy: CircuitBreaker) -> Self {
+		Self { policy, states: Default::default() }
+	}
+
+	/// Fails fast if the circuit of `endpoint` is open.
+	pub(crate) fn allow(&self, endpoint: &str) -> Result<()> {
+		let mut states = self.states.lock().expect("lock must succeed; qed");
+		let Some(state) = states.get_mut(endpoint) else { return Ok(()) };
+		let Some(opened_at) = state.opened_at else { return Ok(()) };
+		let elapsed = opened_at.elapsed();
+
+		if elapsed < self.policy.cooldown {
+			return Err(Error::CircuitOpen {
+				endpoint: endpoint.into(),
+				retry_after: Some(self.policy.cooldown - elapsed),
+			});
+		}
+		// Half-open, only one probe at a time.
+		if let Some(probing) = state.probing_since.map(|t| t.elapsed())
+			&& probing < self.policy.cooldown
+		{
+			return Err(Error::CircuitOpen {
+				endpoint: endpoint.into(),
+				retry_after: Some(self.policy.cooldown - probing),
+			});
+		}
+
+		state.probing_since = Some(Instant::now());
+
+		Ok(())
+	}
+
+	/// Records the outcome of a call to `endpoint`.
+	pub(crate) fn record(&self, endpoint: &str, success: bool) {
+		let mut states = self.states.lock().expect("lock must succeed; qed");
+
+		if success {
+			states.remove(endpoint);
+
+			return;
+		}
+
+		let state = states.entry(endpoint.into()).or_default();
+
+		state.failures += 1;
+
+		if state.probing_since.is_some() || state.failures >= self.policy.failure_threshold {
+			tracing::debug!("opening circuit of {endpoint} after {} failures", state.failures);
+
+			state.opened_at = Some(Instant::now());
+			state.probing_since = None;
+		}
+	}
+}
+
+#[test]
+fn circuits_should_work() {
+	let cooldown = Duration::from_millis(20);
+	let circuits = Circuits::new(CircuitBreaker { failure_threshold: 2, cooldown });
+
+	circuits.record("/a", false);
+
+	assert!(circuits.allow("/a").is_ok());
+
+	circuits.record("/a", false);
+
+	assert!(matches!(circuits.allow("/a"), Err(Error::CircuitOpen { retry_after: Some(_), .. })));
+	assert!(circuits.allow("/b").is_ok());
+
+	// Cooldown elapsed, the first call probes and the second one fails fast.
+	std::thread::sleep(cooldown);
+
+	assert!(circuits.allow("/a").is_ok());
+	assert!(circuits.allow("/a").is_err());
+
+	// The probe never reported back, another one is let through.
+	std::thread::sleep(cooldown);
+
+	assert!(circuits.allow("/a").is_ok());
+
+	circuits.record("/a", true);
+
+	assert!(circuits.allow("/a").is_ok());
+	assert!(circuits.allow("/a").is_ok());
+}
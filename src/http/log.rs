@@ -0,0 +1,218 @@
+//! Request logging for audit trails.
+
+// std
+use std::{
+	sync::LazyLock,
+	time::{Duration, Instant},
+};
+// crates.io
+use regex::Regex;
+use reqwest::{
+	Method, RequestBuilder, StatusCode,
+	header::{AUTHORIZATION, HeaderMap, HeaderValue},
+};
+// self
+use super::budget::usage_from_json;
+use crate::_prelude::*;
+
+/// Logged bodies are cut after this many bytes.
+const MAX_BODY_LEN: usize = 2_048;
+/// Logged in place of bodies which are streamed, e.g. multipart uploads.
+const STREAMING_BODY: &str = "<streaming body>";
+/// Object fields whose string values are never logged.
+const SECRET_FIELDS: &[&str] =
+	&["access_token", "api_key", "authorization", "client_secret", "password", "secret", "token"];
+
+/// Bearer tokens and OpenAI style keys appearing anywhere in a body.
+static RE_SECRET: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"(Bearer\s+|sk-)[A-Za-z0-9._\-]+").unwrap());
+
+/// Receiver of sanitized request summaries, see [`Api::log_sink`].
+///
+/// Implemented for closures taking a [`LogRecord`].
+pub trait LogSink
+where
+	Self: Send + Sync,
+{
+	/// Called once per call, after its response was read or it failed.
+	fn log(&self, record: &LogRecord);
+}
+impl<F> LogSink for F
+where
+	F: Send + Sync + Fn(&LogRecord),
+{
+	fn log(&self, record: &LogRecord) {
+		self(record)
+	}
+}
+
+/// Sanitized summary of a call handed to a [`LogSink`].
+///
+/// Bodies are truncated and have secrets redacted, streamed request bodies are logged as
+/// `<streaming body>`. Binary and streaming calls are logged once the response headers arrive,
+/// without response body and token counts.
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+	/// HTTP method.
+	pub method: Method,
+	/// Request path, without query.
+	pub endpoint: String,
+	/// Request headers, sensitive values replaced by `***`.
+	pub headers: HeaderMap,
+	/// `model` of the request body, if any.
+	pub model: Option<String>,
+	/// HTTP status, `None` if no response was received.
+	pub status: Option<StatusCode>,
+	/// Input tokens reported by the response.
+	pub input_tokens: Option<u64>,
+	/// Output tokens reported by the response.
+	pub output_tokens: Option<u64>,
+	/// Time from sending the request until the response was read.
+	pub latency: Duration,
+	/// Redacted JSON request body.
+	pub request_body: Option<String>,
+	/// Redacted response body.
+	pub response_body: Option<String>,
+	/// Error the call failed with.
+	pub error: Option<String>,
+}
+impl LogRecord {
+	/// Builds `request` to summarize it, returning it rebuilt for sending.
+	pub(crate) fn new(request: RequestBuilder) -> Result<(RequestBuilder, Self, Instant)> {
+		let (client, request) = request.build_split();
+		let request = request?;
+		let body = request.body().map(|b| match b.as_bytes() {
+			Some(b) => String::from_utf8_lossy(b),
+			None => Cow::Borrowed(STREAMING_BODY),
+		});
+		let model = body
+			.as_deref()
+			.and_then(|b| serde_json::from_str::<Value>(b).ok())
+			.and_then(|v| v.get("model")?.as_str().map(Into::into));
+		let mut headers = request.headers().clone();
+
+		for (name, value) in headers.iter_mut() {
+			if value.is_sensitive() || name == AUTHORIZATION || name.as_str().contains("key") {
+				*value = HeaderValue::from_static("***");
+			}
+		}
+
+		let record = Self {
+			method: request.method().clone(),
+			endpoint: request.url().path().into(),
+			headers,
+			model,
+			status: None,
+			input_tokens: None,
+			output_tokens: None,
+			latency: Duration::ZERO,
+			request_body: body.as_deref().map(redact),
+			response_body: None,
+			error: None,
+		};
+
+		Ok((RequestBuilder::from_parts(client, request), record, Instant::now()))
+	}
+
+	pub(crate) fn finish<T>(mut self, start: Instant, res: &Result<T>, body: Option<&str>) -> Self {
+		self.latency = start.elapsed();
+
+		if let Some(body) = body {
+			if let Some(tokens) = usage_from_json(body) {
+				self.input_tokens = Some(tokens.input);
+				self.output_tokens = Some(tokens.output);
+			}
+
+			self.response_body = Some(redact(body));
+		}
+		if let Err(e) = res {
+			match e {
+				Error::Http { status, .. } => self.status = Some(*status),
+				Error::RateLimited { .. } => self.status = Some(StatusCode::TOO_MANY_REQUESTS),
+				_ => (),
+			}
+
+			self.error = Some(redact(&e.to_string()));
+		}
+
+		self
+	}
+}
+
+/// Redacts secrets in `body` and truncates it to [`MAX_BODY_LEN`].
+fn redact(body: &str) -> String {
+	fn walk(value: &mut Value) {
+		match value {
+			Value::Object(map) =>
+				for (k, v) in map.iter_mut() {
+					if v.is_string() && SECRET_FIELDS.contains(&k.to_ascii_lowercase().as_str()) {
+						*v = Value::String("***".into());
+					} else {
+						walk(v);
+					}
+				},
+			Value::Array(values) => values.iter_mut().for_each(walk),
+			_ => (),
+		}
+	}
+
+	let body = match serde_json::from_str::<Value>(body) {
+		Ok(mut v) => {
+			walk(&mut v);
+
+			v.to_string()
+		},
+		Err(_) => body.into(),
+	};
+	let mut body = RE_SECRET.replace_all(&body, "$1***").into_owned();
+
+	if body.len() > MAX_BODY_LEN {
+		let end = (0..=MAX_BODY_LEN).rev().find(|&i| body.is_char_boundary(i)).unwrap_or(0);
+
+		body.truncate(end);
+		body.push('…');
+	}
+
+	body
+}
+
+#[test]
+fn redact_should_work() {
+	assert_eq!(
+		redact(r#"{"api_key":"abc","input":"use sk-proj-123","max_tokens":1}"#),
+		r#"{"api_key":"***","input":"use sk-***","max_tokens":1}"#
+	);
+	assert_eq!(redact("Authorization: Bearer a.b.c"), "Authorization: Bearer ***");
+	assert_eq!(redact(&"a".repeat(MAX_BODY_LEN + 1)).len(), MAX_BODY_LEN + '…'.len_utf8());
+}
+
+#[test]
+fn log_record_should_cover_uploads() {
+	let request = reqwest::Client::new()
+		.post("https://api.openai.com/v1/files")
+		.header("api-key", "sk-foo")
+		.multipart(reqwest::multipart::Form::new().text("purpose", "batch"));
+	let (_, record, _) = LogRecord::new(request).unwrap();
+
+	assert_eq!(record.endpoint, "/v1/files");
+	assert_eq!(record.headers["api-key"], "***");
+	assert_eq!(record.request_body.as_deref(), Some(STREAMING_BODY));
+}
+
+#[test]
+fn log_record_should_keep_error_status() {
+	let request = reqwest::Client::new().get("https://api.openai.com/v1/models");
+	let (_, record, start) = LogRecord::new(request).unwrap();
+	let res = Err::<(), _>(Error::Http {
+		status: StatusCode::NOT_FOUND,
+		headers: Default::default(),
+		body: r#"{"error":{"message":"m","type":"invalid_request_error"}}"#.into(),
+		request_id: Some("req_1".into()),
+		api: serde_json::from_value(
+			serde_json::json!({ "message": "m", "type": "invalid_request_error" }),
+		)
+		.ok(),
+	});
+
+	assert_eq!(record.finish(start, &res, None).status, Some(StatusCode::NOT_FOUND));
+}
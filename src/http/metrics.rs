@@ -0,0 +1,50 @@
+//! Instrumentation through the [`metrics`] facade.
+//!
+//! Emitted metrics:
+//! - `openagent_requests_total{method, endpoint, status}`, counter
+//! - `openagent_request_duration_seconds{method, endpoint}`, histogram
+//! - `openagent_retries_total{method, endpoint, status}`, counter
+//! - `openagent_tokens_total{direction}`, counter, `direction` is `input` or `output`
+
+// std
+use std::time::Duration;
+// crates.io
+use reqwest::{Method, StatusCode};
+
+pub(super) fn record_request(
+	method: &Method,
+	endpoint: &str,
+	status: StatusCode,
+	elapsed: Duration,
+) {
+	let (method, endpoint) = (method.to_string(), endpoint.to_owned());
+
+	metrics::counter!(
+		"openagent_requests_total",
+		"method" => method.clone(),
+		"endpoint" => endpoint.clone(),
+		"status" => status.as_u16().to_string(),
+	)
+	.increment(1);
+	metrics::histogram!(
+		"openagent_request_duration_seconds",
+		"method" => method,
+		"endpoint" => endpoint,
+	)
+	.record(elapsed);
+}
+
+pub(super) fn record_retry(method: &Method, endpoint: &str, status: StatusCode) {
+	metrics::counter!(
+		"openagent_retries_total",
+		"method" => method.to_string(),
+		"endpoint" => endpoint.to_owned(),
+		"status" => status.as_u16().to_string(),
+	)
+	.increment(1);
+}
+
+pub(super) fn record_usage(input: u64, output: u64) {
+	metrics::counter!("openagent_tokens_total", "direction" => "input").increment(input);
+	metrics::counter!("openagent_tokens_total", "direction" => "output").increment(output);
+}
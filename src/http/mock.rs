@@ -0,0 +1,351 @@
+//! In-memory [`ApiBase`] implementation for tests.
+
+// std
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Mutex},
+};
+// crates.io
+use futures::stream;
+use reqwest::{Method, StatusCode, header::HeaderMap};
+use tokio_util::{
+	bytes::Bytes,
+	codec::{FramedRead, LinesCodec},
+	io::StreamReader,
+};
+// self
+use super::{ByteStream, Sse};
+use crate::_prelude::*;
+
+type Responses = HashMap<(Method, String), VecDeque<MockResponse>>;
+
+/// Client serving programmed responses instead of talking to the network.
+///
+/// Responses are queued per method and endpoint and consumed in order. A request without a
+/// queued response fails with [`Error::Any`].
+#[derive(Clone, Debug, Default)]
+pub struct MockApi {
+	base_uri: String,
+	responses: Arc<Mutex<Responses>>,
+	requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+impl MockApi {
+	/// Creates a mock without any programmed response.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the URI reported by [`ApiBase::base_uri`].
+	pub fn base_uri(mut self, base_uri: impl Into<String>) -> Self {
+		self.base_uri = base_uri.into();
+
+		self
+	}
+
+	/// Queues `response` for the next `method` request to `endpoint`.
+	pub fn respond(
+		&self,
+		method: Method,
+		endpoint: impl Into<String>,
+		response: MockResponse,
+	) -> &Self {
+		self.responses
+			.lock()
+			.expect("lock must succeed; qed")
+			.entry((method, endpoint.into()))
+			.or_default()
+			.push_back(response);
+
+		self
+	}
+
+	/// Returns the requests received so far, oldest first.
+	pub fn requests(&self) -> Vec<MockRequest> {
+		self.requests.lock().expect("lock must succeed; qed").clone()
+	}
+
+	fn next(&self, method: Method, endpoint: &str, body: Option<Value>) -> Result<MockResponse> {
+		tracing::debug!("{method} {endpoint}");
+
+		self.requests.lock().expect("lock must succeed; qed").push(MockRequest {
+			method: method.clone(),
+			endpoint: endpoint.into(),
+			body,
+		});
+		self.responses
+			.lock()
+			.expect("lock must succeed; qed")
+			.get_mut(&(method.clone(), endpoint.into()))
+			.and_then(VecDeque::pop_front)
+			.ok_or_else(|| Error::any(format!("no mock response for {method} {endpoint}")))
+	}
+
+	fn text(&self, method: Method, endpoint: &str, body: Option<Value>) -> Result<String> {
+		self.next(method, endpoint, body)?.into_text()
+	}
+}
+impl ApiBase for MockApi {
+	fn base_uri(&self) -> &str {
+		&self.base_uri
+	}
+
+	async fn get(&self, endpoint: &str) -> Result<String> {
+		self.text(Method::GET, endpoint, None)
+	}
+
+	async fn get_bytes(&self, endpoint: &str) -> Result<Bytes> {
+		Ok(self.next(Method::GET, endpoint, None)?.into_chunks()?.concat().into())
+	}
+
+	async fn get_bytes_stream(&self, endpoint: &str) -> Result<EventStream<Bytes>> {
+		let chunks = self.next(Method::GET, endpoint, None)?.into_chunks()?;
+
+		Ok(Box::pin(stream::iter(chunks.into_iter().map(Ok))))
+	}
+
+	async fn delete(&self, endpoint: &str) -> Result<String> {
+		self.text(Method::DELETE, endpoint, None)
+	}
+
+	async fn post_multipart(&self, endpoint: &str, _: Multipart) -> Result<String> {
+		self.text(Method::POST, endpoint, None)
+	}
+
+	async fn post_json<S>(&self, endpoint: &str, body: S) -> Result<String>
+	where
+		S: Send + Serialize,
+	{
+		self.text(Method::POST, endpoint, Some(serde_json::to_value(body)?))
+	}
+
+	async fn post_bytes<S>(&self, endpoint: &str, body: S) -> Result<Bytes>
+	where
+		S: Send + Serialize,
+	{
+		let body = Some(serde_json::to_value(body)?);
+
+		Ok(self.next(Method::POST, endpoint, body)?.into_chunks()?.concat().into())
+	}
+
+	async fn post_bytes_stream<S>(&self, endpoint: &str, body: S) -> Result<EventStream<Bytes>>
+	where
+		S: Send + Serialize,
+	{
+		let chunks =
+			self.next(Method::POST, endpoint, Some(serde_json::to_value(body)?))?.into_chunks()?;
+
+		Ok(Box::pin(stream::iter(chunks.into_iter().map(Ok))))
+	}
+
+	async fn sse<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		self.sse_with_resume(endpoint, body, options, None).await
+	}
+
+	async fn sse_with_resume<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+		last_event_id: Option<&str>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		let chunks =
+			self.next(Method::POST, endpoint, Some(serde_json::to_value(body)?))?.into_chunks()?;
+		let stream = Box::pin(stream::iter(chunks.into_iter().map(Ok))) as ByteStream;
+		let cancelled = options.cancellation.clone().map(|t| Box::pin(t.cancelled_owned()));
+
+		Ok(Box::pin(Sse {
+			stream: FramedRead::new(StreamReader::new(stream), LinesCodec::new()),
+			options,
+			last_event: (None, last_event_id.map(Into::into)),
+			data: Default::default(),
+			unexpected: Default::default(),
+			connect: None,
+			reconnecting: None,
+			retries: 0,
+			idle: None,
+			cancelled,
+			budget: None,
+		}))
+	}
+}
+
+/// Programmed response of a [`MockApi`].
+#[derive(Clone, Debug)]
+pub enum MockResponse {
+	/// Successful response with the given body.
+	Body(String),
+	/// Scripted stream, each item is sent as one SSE `data:` event.
+	Events(Vec<String>),
+	/// Raw chunks of a streamed body, e.g. hand-written SSE frames or NDJSON lines.
+	Chunks(Vec<Bytes>),
+	/// Failed response with the given status and body, a `429` other than an exhausted quota
+	/// fails with [`Error::RateLimited`] as the retries of [`Api`] gave up.
+	Status(StatusCode, String),
+}
+impl MockResponse {
+	/// Successful response with `body` serialized as JSON.
+	pub fn json<T>(body: &T) -> Self
+	where
+		T: Serialize,
+	{
+		Self::Body(serde_json::to_string(body).expect("serialization must succeed; qed"))
+	}
+
+	fn into_chunks(self) -> Result<Vec<Bytes>> {
+		match self {
+			Self::Body(body) => Ok(vec![body.into()]),
+			Self::Events(events) => Ok(events
+				.into_iter()
+				.map(|e| format!("data: {e}\n\n").into())
+				.chain([Bytes::from("data: [DONE]\n\n")])
+				.collect()),
+			Self::Chunks(chunks) => Ok(chunks),
+			Self::Status(status, body) => {
+				let api =
+					serde_json::from_str::<ApiErrorWrapper>(&body).ok().map(|e| Box::new(e.error));
+				let e = Error::Http {
+					status,
+					headers: Box::new(HeaderMap::new()),
+					body,
+					request_id: None,
+					api,
+				};
+
+				// Same as `Api`, only an exhausted quota is not a rate limit.
+				if status == StatusCode::TOO_MANY_REQUESTS
+					&& e.api_kind() != Some(ApiErrorKind::InsufficientQuota)
+				{
+					Err(Error::RateLimited { retry_after: None, limit_info: None })
+				} else {
+					Err(e)
+				}
+			},
+		}
+	}
+
+	fn into_text(self) -> Result<String> {
+		let chunks = self.into_chunks()?;
+
+		Ok(chunks.iter().map(|c| String::from_utf8_lossy(c)).collect())
+	}
+}
+
+/// Request received by a [`MockApi`].
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+	/// HTTP method.
+	pub method: Method,
+	/// Endpoint, relative to the base URI.
+	pub endpoint: String,
+	/// JSON body, if any.
+	pub body: Option<Value>,
+}
+
+/// Chat completion stream chunk carrying `content`, an event of [`MockResponse::Events`].
+#[cfg(test)]
+pub(crate) fn mock_chunk(content: &str) -> String {
+	serde_json::json!({
+		"choices": [{ "delta": { "content": content }, "index": 0 }],
+		"created": 0,
+		"id": "c",
+		"model": "gpt-4o",
+	})
+	.to_string()
+}
+
+/// Final chat completion stream chunk reporting the usage of the stream.
+#[cfg(test)]
+pub(crate) fn mock_usage_chunk(prompt_tokens: u32, completion_tokens: u32) -> String {
+	serde_json::json!({
+		"choices": [],
+		"created": 0,
+		"id": "c",
+		"model": "gpt-4o",
+		"usage": {
+			"completion_tokens": completion_tokens,
+			"prompt_tokens": prompt_tokens,
+			"total_tokens": prompt_tokens + completion_tokens,
+		},
+	})
+	.to_string()
+}
+
+/// Chat completion answering `content`, using one prompt and one completion token.
+#[cfg(test)]
+pub(crate) fn mock_chat(content: &str) -> MockResponse {
+	MockResponse::json(&serde_json::json!({
+		"choices": [{
+			"finish_reason": "stop",
+			"index": 0,
+			"message": { "content": content, "role": "assistant" },
+		}],
+		"created": 0,
+		"id": "c",
+		"model": "gpt-4o",
+		"usage": { "completion_tokens": 1, "prompt_tokens": 1, "total_tokens": 2 },
+	}))
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn mock_api_should_work() {
+	// crates.io
+	use futures::TryStreamExt;
+
+	let api = MockApi::new();
+
+	api.respond(Method::GET, "/files/a", MockResponse::Body("a".into()))
+		.respond(
+			Method::POST,
+			"/chat/completions",
+			MockResponse::Events(vec!["x".into(), "y".into()]),
+		)
+		.respond(Method::DELETE, "/files/a", MockResponse::Status(StatusCode::NOT_FOUND, "".into()))
+		.respond(
+			Method::DELETE,
+			"/files/b",
+			MockResponse::Status(StatusCode::TOO_MANY_REQUESTS, "".into()),
+		)
+		.respond(
+			Method::DELETE,
+			"/files/c",
+			MockResponse::Status(
+				StatusCode::TOO_MANY_REQUESTS,
+				r#"{"error":{"message":"m","type":null,"param":null,"code":"429"}}"#.into(),
+			),
+		);
+
+	assert_eq!(api.get("/files/a").await.unwrap(), "a");
+	assert!(api.get("/files/a").await.is_err());
+	assert!(matches!(
+		api.delete("/files/a").await,
+		Err(Error::Http { status: StatusCode::NOT_FOUND, .. })
+	));
+	assert!(matches!(api.delete("/files/b").await, Err(Error::RateLimited { .. })));
+	assert!(matches!(api.delete("/files/c").await, Err(Error::RateLimited { .. })));
+
+	let events = api
+		.sse("/chat/completions", serde_json::json!({ "stream": true }), SseOptions::new(()))
+		.await
+		.unwrap()
+		.try_collect::<Vec<_>>()
+		.await
+		.unwrap();
+
+	assert_eq!(events, ["x", "y"]);
+	assert_eq!(api.requests().len(), 6);
+	assert_eq!(api.requests()[5].body, Some(serde_json::json!({ "stream": true })));
+}
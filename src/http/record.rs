@@ -0,0 +1,461 @@
+//! Recording and byte-for-byte replay of responses, for deterministic agent runs.
+
+// std
+use std::sync::{
+	Arc, Mutex,
+	atomic::{AtomicUsize, Ordering},
+};
+// crates.io
+use futures::{StreamExt, TryStreamExt, stream};
+use tokio_util::bytes::Bytes;
+// self
+use crate::_prelude::*;
+
+/// Responses received in order, captured by [`RecordingApi`] and served by [`ReplayApi`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recording {
+	/// Exchanges, oldest first.
+	pub exchanges: Vec<RecordedExchange>,
+}
+impl Recording {
+	fn push(
+		recording: &Mutex<Self>,
+		method: &str,
+		endpoint: &str,
+		body: Option<Value>,
+		response: RecordedResponse,
+	) -> usize {
+		let mut recording = recording.lock().expect("lock must succeed; qed");
+
+		recording.exchanges.push(RecordedExchange {
+			method: method.into(),
+			endpoint: endpoint.into(),
+			body,
+			response,
+		});
+
+		recording.exchanges.len() - 1
+	}
+
+	fn extend(recording: &Mutex<Self>, index: usize, f: impl FnOnce(&mut RecordedResponse)) {
+		f(&mut recording.lock().expect("lock must succeed; qed").exchanges[index].response);
+	}
+}
+
+/// Request and response pair of a [`Recording`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+	/// HTTP method.
+	pub method: String,
+	/// Endpoint, relative to the base URI.
+	pub endpoint: String,
+	/// JSON body of the request, if any.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub body: Option<Value>,
+	/// Response as received.
+	pub response: RecordedResponse,
+}
+
+/// Response of a [`RecordedExchange`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum RecordedResponse {
+	/// Text body.
+	Body(String),
+	/// Binary body, streamed ones concatenated.
+	Bytes(Vec<u8>),
+	/// Data of every streamed event.
+	Events(Vec<String>),
+}
+
+/// Client recording the responses of `A`, see [`RecordingApi::recording`].
+///
+/// Failed requests other than streams are not recorded.
+#[derive(Clone, Debug)]
+pub struct RecordingApi<A> {
+	api: A,
+	recording: Arc<Mutex<Recording>>,
+}
+impl<A> RecordingApi<A> {
+	/// Records the responses of `api`.
+	pub fn new(api: A) -> Self {
+		Self { api, recording: Default::default() }
+	}
+
+	/// Returns the responses recorded so far.
+	pub fn recording(&self) -> Recording {
+		self.recording.lock().expect("lock must succeed; qed").clone()
+	}
+
+	fn record<T>(
+		&self,
+		method: &str,
+		endpoint: &str,
+		body: Option<Value>,
+		res: Result<T>,
+		f: impl FnOnce(&T) -> RecordedResponse,
+	) -> Result<T> {
+		if let Ok(v) = &res {
+			Recording::push(&self.recording, method, endpoint, body, f(v));
+		}
+
+		res
+	}
+
+	fn tee(
+		&self,
+		method: &str,
+		endpoint: &str,
+		body: Option<Value>,
+		stream: EventStream<Bytes>,
+	) -> EventStream<Bytes> {
+		let recording = self.recording.clone();
+		let index = Recording::push(
+			&self.recording,
+			method,
+			endpoint,
+			body,
+			RecordedResponse::Bytes(Vec::new()),
+		);
+
+		Box::pin(stream.inspect_ok(move |chunk| {
+			Recording::extend(&recording, index, |r| {
+				if let RecordedResponse::Bytes(bytes) = r {
+					bytes.extend_from_slice(chunk);
+				}
+			})
+		}))
+	}
+}
+impl<A> ApiBase for RecordingApi<A>
+where
+	A: ApiBase,
+{
+	fn base_uri(&self) -> &str {
+		self.api.base_uri()
+	}
+
+	async fn get(&self, endpoint: &str) -> Result<String> {
+		let res = self.api.get(endpoint).await;
+
+		self.record("GET", endpoint, None, res, |b| RecordedResponse::Body(b.clone()))
+	}
+
+	async fn get_bytes(&self, endpoint: &str) -> Result<Bytes> {
+		let res = self.api.get_bytes(endpoint).await;
+
+		self.record("GET", endpoint, None, res, |b| RecordedResponse::Bytes(b.to_vec()))
+	}
+
+	async fn get_bytes_stream(&self, endpoint: &str) -> Result<EventStream<Bytes>> {
+		Ok(self.tee("GET", endpoint, None, self.api.get_bytes_stream(endpoint).await?))
+	}
+
+	async fn delete(&self, endpoint: &str) -> Result<String> {
+		let res = self.api.delete(endpoint).await;
+
+		self.record("DELETE", endpoint, None, res, |b| RecordedResponse::Body(b.clone()))
+	}
+
+	async fn post_multipart(&self, endpoint: &str, multipart: Multipart) -> Result<String> {
+		let res = self.api.post_multipart(endpoint, multipart).await;
+
+		self.record("POST", endpoint, None, res, |b| RecordedResponse::Body(b.clone()))
+	}
+
+	async fn post_json<S>(&self, endpoint: &str, body: S) -> Result<String>
+	where
+		S: Send + Serialize,
+	{
+		let json = serde_json::to_value(&body)?;
+		let res = self.api.post_json(endpoint, body).await;
+
+		self.record("POST", endpoint, Some(json), res, |b| RecordedResponse::Body(b.clone()))
+	}
+
+	async fn post_bytes<S>(&self, endpoint: &str, body: S) -> Result<Bytes>
+	where
+		S: Send + Serialize,
+	{
+		let json = serde_json::to_value(&body)?;
+		let res = self.api.post_bytes(endpoint, body).await;
+
+		self.record("POST", endpoint, Some(json), res, |b| RecordedResponse::Bytes(b.to_vec()))
+	}
+
+	async fn post_bytes_stream<S>(&self, endpoint: &str, body: S) -> Result<EventStream<Bytes>>
+	where
+		S: Send + Serialize,
+	{
+		let json = serde_json::to_value(&body)?;
+		let stream = self.api.post_bytes_stream(endpoint, body).await?;
+
+		Ok(self.tee("POST", endpoint, Some(json), stream))
+	}
+
+	async fn sse<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		self.sse_with_resume(endpoint, body, options, None).await
+	}
+
+	async fn sse_with_resume<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+		last_event_id: Option<&str>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		let SseOptions { drop_event, event_handler, reconnect, idle_timeout, cancellation, format } =
+			options;
+		let handler = RecordingHandler {
+			handler: event_handler,
+			recording: self.recording.clone(),
+			index: Recording::push(
+				&self.recording,
+				"POST",
+				endpoint,
+				Some(serde_json::to_value(&body)?),
+				RecordedResponse::Events(Vec::new()),
+			),
+		};
+		let options = SseOptions {
+			drop_event,
+			event_handler: handler,
+			reconnect,
+			idle_timeout,
+			cancellation,
+			format,
+		};
+
+		self.api.sse_with_resume(endpoint, body, options, last_event_id).await
+	}
+}
+
+/// Handler recording the data of every event before passing it on.
+struct RecordingHandler<H> {
+	handler: H,
+	recording: Arc<Mutex<Recording>>,
+	index: usize,
+}
+impl<H> EventHandler for RecordingHandler<H>
+where
+	H: EventHandler,
+{
+	type Event = H::Event;
+
+	fn handle_event(&self, event: &str) -> Result<()> {
+		self.handler.handle_event(event)
+	}
+
+	fn handle_data(&self, data: String) -> Result<Self::Event> {
+		Recording::extend(&self.recording, self.index, |r| {
+			if let RecordedResponse::Events(events) = r {
+				events.push(data.clone());
+			}
+		});
+
+		self.handler.handle_data(data)
+	}
+
+	fn handle_unexpected(&self, unexpected: String) -> Result<()> {
+		self.handler.handle_unexpected(unexpected)
+	}
+}
+
+/// Client serving a [`Recording`] in order instead of talking to the network.
+///
+/// A request other than the recorded one at its position, by method, endpoint or JSON body, fails
+/// with [`Error::Any`], pointing at the first divergence of the replayed run.
+#[derive(Clone, Debug)]
+pub struct ReplayApi {
+	recording: Arc<Recording>,
+	cursor: Arc<AtomicUsize>,
+}
+impl ReplayApi {
+	/// Replays `recording` from its first exchange.
+	pub fn new(recording: Recording) -> Self {
+		Self { recording: Arc::new(recording), cursor: Default::default() }
+	}
+
+	/// Returns the number of exchanges served so far.
+	pub fn position(&self) -> usize {
+		self.cursor.load(Ordering::SeqCst)
+	}
+
+	fn next(&self, method: &str, endpoint: &str, body: Option<Value>) -> Result<RecordedResponse> {
+		let i = self.cursor.fetch_add(1, Ordering::SeqCst);
+		let Some(exchange) = self.recording.exchanges.get(i) else {
+			Err(Error::any(format!("replay exhausted at {method} {endpoint}")))?
+		};
+
+		if exchange.method != method || exchange.endpoint != endpoint {
+			Err(Error::any(format!(
+				"replay diverged at exchange {i}: recorded {} {}, got {method} {endpoint}",
+				exchange.method, exchange.endpoint
+			)))?
+		}
+		if exchange.body != body {
+			Err(Error::any(format!(
+				"replay diverged at exchange {i}: body of {method} {endpoint} differs from the \
+				recorded one"
+			)))?
+		}
+
+		Ok(exchange.response.clone())
+	}
+
+	fn text(&self, method: &str, endpoint: &str, body: Option<Value>) -> Result<String> {
+		match self.next(method, endpoint, body)? {
+			RecordedResponse::Body(body) => Ok(body),
+			RecordedResponse::Bytes(bytes) => Ok(String::from_utf8_lossy(&bytes).into()),
+			RecordedResponse::Events(_) => Err(Error::any("recorded response is a stream")),
+		}
+	}
+
+	fn bytes(&self, method: &str, endpoint: &str, body: Option<Value>) -> Result<Bytes> {
+		match self.next(method, endpoint, body)? {
+			RecordedResponse::Body(body) => Ok(body.into()),
+			RecordedResponse::Bytes(bytes) => Ok(bytes.into()),
+			RecordedResponse::Events(_) => Err(Error::any("recorded response is a stream")),
+		}
+	}
+}
+impl ApiBase for ReplayApi {
+	fn base_uri(&self) -> &str {
+		""
+	}
+
+	async fn get(&self, endpoint: &str) -> Result<String> {
+		self.text("GET", endpoint, None)
+	}
+
+	async fn get_bytes(&self, endpoint: &str) -> Result<Bytes> {
+		self.bytes("GET", endpoint, None)
+	}
+
+	async fn get_bytes_stream(&self, endpoint: &str) -> Result<EventStream<Bytes>> {
+		let bytes = self.bytes("GET", endpoint, None)?;
+
+		Ok(Box::pin(stream::iter([Ok(bytes)])))
+	}
+
+	async fn delete(&self, endpoint: &str) -> Result<String> {
+		self.text("DELETE", endpoint, None)
+	}
+
+	async fn post_multipart(&self, endpoint: &str, _: Multipart) -> Result<String> {
+		self.text("POST", endpoint, None)
+	}
+
+	async fn post_json<S>(&self, endpoint: &str, body: S) -> Result<String>
+	where
+		S: Send + Serialize,
+	{
+		self.text("POST", endpoint, Some(serde_json::to_value(body)?))
+	}
+
+	async fn post_bytes<S>(&self, endpoint: &str, body: S) -> Result<Bytes>
+	where
+		S: Send + Serialize,
+	{
+		self.bytes("POST", endpoint, Some(serde_json::to_value(body)?))
+	}
+
+	async fn post_bytes_stream<S>(&self, endpoint: &str, body: S) -> Result<EventStream<Bytes>>
+	where
+		S: Send + Serialize,
+	{
+		let bytes = self.bytes("POST", endpoint, Some(serde_json::to_value(body)?))?;
+
+		Ok(Box::pin(stream::iter([Ok(bytes)])))
+	}
+
+	async fn sse<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		self.sse_with_resume(endpoint, body, options, None).await
+	}
+
+	async fn sse_with_resume<S, H>(
+		&self,
+		endpoint: &str,
+		body: S,
+		options: SseOptions<H>,
+		_: Option<&str>,
+	) -> Result<EventStream<H::Event>>
+	where
+		S: Send + Serialize,
+		H: 'static + EventHandler,
+	{
+		let RecordedResponse::Events(events) =
+			self.next("POST", endpoint, Some(serde_json::to_value(body)?))?
+		else {
+			Err(Error::any("recorded response is not a stream"))?
+		};
+		let handler = options.event_handler;
+
+		Ok(Box::pin(stream::iter(events).map(move |data| handler.handle_data(data))))
+	}
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn recording_should_replay() {
+	let api = RecordingApi::new(ReplayApi::new(Recording {
+		exchanges: vec![
+			RecordedExchange {
+				method: "GET".into(),
+				endpoint: "/models".into(),
+				body: None,
+				response: RecordedResponse::Body("m".into()),
+			},
+			RecordedExchange {
+				method: "POST".into(),
+				endpoint: "/chat/completions".into(),
+				body: Some(Value::Null),
+				response: RecordedResponse::Events(vec!["x".into(), "y".into()]),
+			},
+		],
+	}));
+
+	assert_eq!(api.get("/models").await.unwrap(), "m");
+	assert_eq!(
+		api.sse("/chat/completions", (), SseOptions::new(()))
+			.await
+			.unwrap()
+			.try_collect::<Vec<_>>()
+			.await
+			.unwrap(),
+		["x", "y"]
+	);
+
+	let replay = ReplayApi::new(api.recording());
+
+	assert_eq!(replay.get("/models").await.unwrap(), "m");
+	assert!(replay.get("/models").await.is_err());
+	assert_eq!(replay.position(), 2);
+
+	let replay = ReplayApi::new(api.recording());
+
+	assert_eq!(replay.get("/models").await.unwrap(), "m");
+	assert!(replay.sse("/chat/completions", [1], SseOptions::new(())).await.is_err());
+}
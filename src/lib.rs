@@ -3,20 +3,29 @@
 #![deny(clippy::all, missing_docs)]
 #![cfg_attr(not(test), deny(unused_crate_dependencies))]
 
-// pub mod agent;
+pub mod agent;
 pub mod api;
 pub mod error;
 pub mod http;
-// pub mod mcp;
-// pub mod tool;
+#[cfg(feature = "mcp")] pub mod mcp;
+#[cfg(feature = "axum")] pub mod relay;
+#[cfg(feature = "tokenizer")] pub mod tokenizer;
+pub mod tool;
 pub mod r#type;
 
 pub mod prelude {
 	#![allow(missing_docs)]
 
+	#[cfg(feature = "mcp")] pub use crate::mcp::*;
+	#[cfg(feature = "tokenizer")] pub use crate::tokenizer::*;
 	pub use crate::{
-		api::{ApiEventHandler, batch::*, chat::*, embedding::*, file::*, response::*, r#type::*},
+		agent::*,
+		api::{
+			ApiEventHandler, batch::*, chat::*, container::*, embedding::*, file::*, response::*,
+			r#type::*,
+		},
 		http::*,
+		tool::*,
 		r#type::*,
 	};
 }
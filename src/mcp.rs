@@ -1 +1,457 @@
+//! Tools of MCP (Model Context Protocol) servers, backed by `rmcp`.
 
+// std
+use std::{
+	collections::HashMap,
+	sync::{
+		Arc, RwLock,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::{Duration, Instant},
+};
+// crates.io
+use futures::future::BoxFuture;
+use rmcp::{
+	ClientHandler, Peer, RmcpError, RoleClient, Service, ServiceExt,
+	model::{CallToolRequestParam, CallToolResult, ClientInfo, Implementation, RawContent},
+	service::{NotificationContext, RunningService},
+	transport::{IntoTransport, TokioChildProcess},
+};
+use tokio::{process::Command, sync::Mutex};
+// self
+use crate::{_prelude::*, api::response::Tool, tool::ToolT};
+
+/// Tools listed by an MCP server, each callable as a [`ToolT`].
+///
+/// The listed tools are cached. They are listed again once the server announces a change of its
+/// tool list, or once the [`McpToolset::cache_ttl`] elapsed, on the next
+/// [`McpToolset::refresh_if_stale`] or tool call. Schema changes then propagate to the tools
+/// already handed out, tools added by the server only show up in later [`McpToolset::tools`]
+/// calls.
+///
+/// The connection is closed, and a spawned server killed, once the toolset and all of its tools
+/// are dropped.
+#[derive(Clone)]
+pub struct McpToolset {
+	connection: Arc<Connection>,
+}
+impl McpToolset {
+	/// Connects to the MCP server at the other end of `transport` and lists its tools.
+	pub async fn connect<T, E, A>(transport: T) -> Result<Self>
+	where
+		T: IntoTransport<RoleClient, E, A>,
+		E: 'static + Send + Sync + std::error::Error,
+	{
+		let stale = Arc::new(AtomicBool::new(false));
+		let session = serve(transport, stale.clone()).await?;
+
+		Self::new(Connection::new(session, None, stale)).await
+	}
+
+	/// Spawns the stdio MCP server started by `stdio` and lists its tools.
+	///
+	/// A server which exited is spawned again on the next tool call, up to
+	/// [`McpStdio::max_restarts`] times.
+	pub async fn spawn(stdio: McpStdio) -> Result<Self> {
+		let stale = Arc::new(AtomicBool::new(false));
+		let session = stdio.serve(stale.clone()).await?;
+
+		Self::new(Connection::new(session, Some(stdio), stale)).await
+	}
+
+	/// Lists the tools of an already running `service`.
+	///
+	/// Change notifications of the server go to the handler of `service`, so the cache of such a
+	/// toolset only expires through its TTL.
+	pub async fn from_service<S>(service: RunningService<RoleClient, S>) -> Result<Self>
+	where
+		S: Service<RoleClient>,
+	{
+		let session = Session { peer: service.peer().clone(), _service: Box::new(service) };
+
+		Self::new(Connection::new(session, None, Default::default())).await
+	}
+
+	async fn new(connection: Connection) -> Result<Self> {
+		let toolset = Self { connection: Arc::new(connection) };
+
+		toolset.refresh().await?;
+
+		Ok(toolset)
+	}
+
+	/// Sets how long the listed tools are used before they are listed again.
+	///
+	/// Without a TTL, the tools are only listed again on a change notification or a
+	/// [`McpToolset::refresh`].
+	pub fn cache_ttl(self, ttl: Duration) -> Self {
+		self.connection.cache.write().expect("lock must succeed; qed").ttl = Some(ttl);
+
+		self
+	}
+
+	/// Lists the tools again, e.g. after the server announced a change.
+	pub async fn refresh(&self) -> Result<()> {
+		self.connection.list_tools(true).await.map(drop)
+	}
+
+	/// Lists the tools again if the server announced a change or the cache TTL elapsed.
+	///
+	/// Returns whether the tools were listed again.
+	pub async fn refresh_if_stale(&self) -> Result<bool> {
+		self.connection.list_tools(false).await
+	}
+
+	/// Returns the tools, e.g. to register them with an agent.
+	pub fn tools(&self) -> Vec<McpTool> {
+		self.connection
+			.cache
+			.read()
+			.expect("lock must succeed; qed")
+			.tools
+			.iter()
+			.map(|tool| McpTool {
+				connection: self.connection.clone(),
+				description: tool.description.clone().unwrap_or_default().into_owned(),
+				tool: tool.clone(),
+			})
+			.collect()
+	}
+
+	/// Returns the tools as function definitions of a Responses request.
+	pub fn function_tools(&self) -> Vec<Tool> {
+		self.tools()
+			.iter()
+			.map(|t| Tool::Function {
+				name: t.name().into(),
+				parameters: t.schema(),
+				strict: Default::default(),
+				description: t.tool.description.as_ref().map(|d| d.to_string()),
+			})
+			.collect()
+	}
+}
+
+/// Command starting a stdio MCP server, see [`McpToolset::spawn`].
+#[derive(Clone, Debug, Default)]
+pub struct McpStdio {
+	/// Program to run.
+	pub command: String,
+	/// Arguments of the program.
+	pub args: Vec<String>,
+	/// Environment variables set on top of the inherited ones.
+	pub env: HashMap<String, String>,
+	/// Number of times a server which exited is spawned again.
+	pub max_restarts: usize,
+}
+impl McpStdio {
+	/// Runs `command` without arguments.
+	pub fn new(command: impl Into<String>) -> Self {
+		Self { command: command.into(), ..Default::default() }
+	}
+
+	/// Appends `arg` to the arguments.
+	pub fn arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+
+		self
+	}
+
+	/// Sets the environment variable `key` to `value`.
+	pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.env.insert(key.into(), value.into());
+
+		self
+	}
+
+	/// Sets the number of times a server which exited is spawned again.
+	pub fn max_restarts(mut self, max_restarts: usize) -> Self {
+		self.max_restarts = max_restarts;
+
+		self
+	}
+
+	async fn serve(&self, stale: Arc<AtomicBool>) -> Result<Session> {
+		let mut command = Command::new(&self.command);
+
+		command.args(&self.args).envs(&self.env);
+
+		tracing::debug!("spawning MCP server: {} {}", self.command, self.args.join(" "));
+
+		serve(TokioChildProcess::new(command)?, stale).await
+	}
+}
+
+/// Client session with an MCP server, kept open as long as it is referenced.
+struct Session {
+	peer: Peer<RoleClient>,
+	_service: Box<dyn Send + Sync>,
+}
+
+/// Client handler marking the listed tools stale once the server announces a change.
+struct Client {
+	info: ClientInfo,
+	stale: Arc<AtomicBool>,
+}
+impl ClientHandler for Client {
+	fn get_info(&self) -> ClientInfo {
+		self.info.clone()
+	}
+
+	async fn on_tool_list_changed(&self, _: NotificationContext<RoleClient>) {
+		tracing::debug!("MCP tool list changed");
+
+		self.stale.store(true, Ordering::Relaxed);
+	}
+}
+
+/// Tools listed by a server, with when they were listed.
+struct ToolCache {
+	tools: Vec<rmcp::model::Tool>,
+	listed_at: Instant,
+	ttl: Option<Duration>,
+}
+impl ToolCache {
+	fn expired(&self, stale: bool) -> bool {
+		stale || self.ttl.is_some_and(|ttl| self.listed_at.elapsed() >= ttl)
+	}
+}
+
+/// Running session of a toolset, with its cached tools and what it takes to spawn a stdio server
+/// again.
+struct Connection {
+	session: RwLock<Arc<Session>>,
+	stdio: Option<McpStdio>,
+	restarts: Mutex<usize>,
+	stale: Arc<AtomicBool>,
+	cache: RwLock<ToolCache>,
+}
+impl Connection {
+	fn new(session: Session, stdio: Option<McpStdio>, stale: Arc<AtomicBool>) -> Self {
+		Self {
+			session: RwLock::new(Arc::new(session)),
+			stdio,
+			restarts: Mutex::new(0),
+			stale,
+			cache: RwLock::new(ToolCache {
+				tools: Vec::new(),
+				listed_at: Instant::now(),
+				ttl: None,
+			}),
+		}
+	}
+
+	/// Lists the tools unless the cached ones are still fresh, returns whether they were listed.
+	async fn list_tools(&self, force: bool) -> Result<bool> {
+		let stale = self.stale.load(Ordering::Relaxed);
+		let expired = self.cache.read().expect("lock must succeed; qed").expired(stale);
+
+		if !force && !expired {
+			return Ok(false);
+		}
+
+		let session = self.session().await?;
+
+		// Cleared first, so a change announced while listing is not lost.
+		self.stale.store(false, Ordering::Relaxed);
+
+		let tools = match session.peer.list_all_tools().await {
+			Ok(tools) => tools,
+			Err(e) => {
+				self.stale.store(true, Ordering::Relaxed);
+
+				return Err(RmcpError::from(e).into());
+			},
+		};
+
+		tracing::debug!("listed {} MCP tools", tools.len());
+
+		let mut cache = self.cache.write().expect("lock must succeed; qed");
+
+		cache.tools = tools;
+		cache.listed_at = Instant::now();
+
+		Ok(true)
+	}
+
+	/// Returns the current definition of the tool named `name`, if it is still listed.
+	fn tool(&self, name: &str) -> Option<rmcp::model::Tool> {
+		let cache = self.cache.read().expect("lock must succeed; qed");
+
+		cache.tools.iter().find(|t| t.name == name).cloned()
+	}
+
+	/// Returns the running session, spawning the stdio server again if it exited.
+	async fn session(&self) -> Result<Arc<Session>> {
+		let session = self.current();
+		let Some(stdio) = &self.stdio else { return Ok(session) };
+
+		if !session.peer.is_transport_closed() {
+			return Ok(session);
+		}
+
+		let mut restarts = self.restarts.lock().await;
+		let session = self.current();
+
+		// Another call restarted the server in the meantime.
+		if !session.peer.is_transport_closed() {
+			return Ok(session);
+		}
+		if *restarts >= stdio.max_restarts {
+			Err(Error::any(format!("MCP server '{}' exited", stdio.command)))?
+		}
+
+		*restarts += 1;
+
+		tracing::warn!("MCP server '{}' exited, restart {}", stdio.command, *restarts);
+
+		let session = Arc::new(stdio.serve(self.stale.clone()).await?);
+
+		// The restarted server may list other tools.
+		self.stale.store(true, Ordering::Relaxed);
+		*self.session.write().expect("lock must succeed; qed") = session.clone();
+
+		Ok(session)
+	}
+
+	fn current(&self) -> Arc<Session> {
+		self.session.read().expect("lock must succeed; qed").clone()
+	}
+}
+
+/// Tool of an MCP server, see [`McpToolset`].
+#[derive(Clone)]
+pub struct McpTool {
+	connection: Arc<Connection>,
+	tool: rmcp::model::Tool,
+	description: String,
+}
+impl ToolT for McpTool {
+	fn name(&self) -> &str {
+		&self.tool.name
+	}
+
+	fn description(&self) -> &str {
+		&self.description
+	}
+
+	fn schema(&self) -> Value {
+		let schema = match self.connection.tool(&self.tool.name) {
+			Some(tool) => tool.input_schema,
+			None => self.tool.input_schema.clone(),
+		};
+
+		Value::Object(schema.as_ref().clone())
+	}
+
+	fn call(&self, params: Value) -> BoxFuture<'static, Result<Value>> {
+		let (connection, name) = (self.connection.clone(), self.tool.name.clone());
+
+		Box::pin(async move {
+			let arguments = match params {
+				Value::Null => None,
+				Value::Object(arguments) => Some(arguments),
+				_ => Err(ToolError::InvalidArguments {
+					tool: name.to_string(),
+					message: "arguments must be an object".into(),
+				})?,
+			};
+
+			if let Err(e) = connection.list_tools(false).await {
+				tracing::warn!("failed to list MCP tools again: {e}");
+			}
+
+			let res = connection
+				.session()
+				.await?
+				.peer
+				.call_tool(CallToolRequestParam { name, arguments })
+				.await
+				.map_err(RmcpError::from)?;
+
+			convert_result(res)
+		})
+	}
+}
+
+/// Initialize an MCP client session over `transport`, flagging `stale` on tool list changes.
+async fn serve<T, E, A>(transport: T, stale: Arc<AtomicBool>) -> Result<Session>
+where
+	T: IntoTransport<RoleClient, E, A>,
+	E: 'static + Send + Sync + std::error::Error,
+{
+	let info = ClientInfo {
+		client_info: Implementation {
+			name: "openagent".into(),
+			version: env!("CARGO_PKG_VERSION").into(),
+		},
+		..Default::default()
+	};
+	let service = Client { info, stale }.serve(transport).await.map_err(RmcpError::from)?;
+
+	Ok(Session { peer: service.peer().clone(), _service: Box::new(service) })
+}
+
+/// Convert the result of a tool call, preferring its structured content over the text of its
+/// content.
+///
+/// A result flagged as error fails with its text, so it is reported back to the model.
+fn convert_result(res: CallToolResult) -> Result<Value> {
+	let CallToolResult { content, structured_content, is_error } = res;
+	let text = content
+		.unwrap_or_default()
+		.into_iter()
+		.map(|c| match c.raw {
+			RawContent::Text(t) => Ok(t.text),
+			raw => Ok(serde_json::to_string(&raw)?),
+		})
+		.collect::<Result<Vec<_>>>()?
+		.join("\n");
+
+	if is_error == Some(true) {
+		return Err(Error::any(text));
+	}
+
+	Ok(structured_content.unwrap_or(Value::String(text)))
+}
+
+#[test]
+fn convert_result_should_work() {
+	// crates.io
+	use rmcp::model::Content;
+
+	let res = CallToolResult::success(vec![Content::text("a"), Content::text("b")]);
+
+	assert_eq!(convert_result(res).unwrap(), Value::String("a\nb".into()));
+
+	let res = CallToolResult {
+		content: None,
+		structured_content: Some(serde_json::json!({ "a": 1 })),
+		is_error: None,
+	};
+
+	assert_eq!(convert_result(res).unwrap(), serde_json::json!({ "a": 1 }));
+	assert!(convert_result(CallToolResult::error(vec![Content::text("boom")])).is_err());
+}
+
+#[test]
+fn tool_cache_should_expire() {
+	let cache = ToolCache { tools: Vec::new(), listed_at: Instant::now(), ttl: None };
+
+	assert!(!cache.expired(false));
+	assert!(cache.expired(true));
+
+	let cache = ToolCache { ttl: Some(Duration::ZERO), ..cache };
+
+	assert!(cache.expired(false));
+
+	let cache = ToolCache { ttl: Some(Duration::from_secs(60)), ..cache };
+
+	assert!(!cache.expired(false));
+}
+
+#[cfg(all(test, unix))]
+#[tokio::test]
+async fn spawn_should_fail_without_server() {
+	assert!(McpToolset::spawn(McpStdio::new("true").max_restarts(1)).await.is_err());
+}
@@ -0,0 +1,79 @@
+//! Relay of agent events and API streams as [`axum`] server-sent events.
+//!
+//! The relays are responses on their own, a handler can return e.g.
+//! `relay::agent_events(agent.react_stream(state, cancellation).await)` as is.
+
+// std
+use std::convert::Infallible;
+// crates.io
+use axum::response::sse::{Event, KeepAlive, KeepAliveStream, Sse};
+use futures::{Stream, StreamExt};
+// self
+use crate::{_prelude::*, agent::AgentEvent};
+
+/// Relay `events` as server-sent events named after their `type`, e.g. `finalAnswer`.
+pub fn agent_events<S>(
+	events: S,
+) -> Sse<KeepAliveStream<impl Send + Stream<Item = Result<Event, Infallible>>>>
+where
+	S: 'static + Send + Stream<Item = AgentEvent>,
+{
+	Sse::new(events.map(|e| Ok(agent_event(&e)))).keep_alive(KeepAlive::default())
+}
+
+/// Convert `event` into a server-sent event named after its `type`, with the event as JSON data.
+pub fn agent_event(event: &AgentEvent) -> Event {
+	let data = serde_json::to_value(event).expect("serialization must succeed; qed");
+	let name = data["type"].as_str().unwrap_or("message").to_owned();
+
+	Event::default().event(name).data(data.to_string())
+}
+
+/// Relay an API event stream, e.g. one of `create_chat_stream`, as server-sent events.
+///
+/// Every item is sent as JSON data of an unnamed event, an error as an `error` event carrying its
+/// message, and the stream ends with a `[DONE]` event as the OpenAI API does.
+pub fn api_events<S, T>(
+	events: S,
+) -> Sse<KeepAliveStream<impl Send + Stream<Item = Result<Event, Infallible>>>>
+where
+	S: 'static + Send + Stream<Item = Result<T>>,
+	T: Serialize,
+{
+	let events = events
+		.map(|e| {
+			Ok(match e.and_then(|e| Ok(serde_json::to_string(&e)?)) {
+				Ok(data) => Event::default().data(data),
+				Err(e) => Event::default().event("error").data(e.to_string()),
+			})
+		})
+		.chain(futures::stream::once(async { Ok(Event::default().data("[DONE]")) }));
+
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn relay_should_work() {
+	// crates.io
+	use axum::{body, response::IntoResponse};
+
+	let body = |res: axum::response::Response| async {
+		String::from_utf8(body::to_bytes(res.into_body(), usize::MAX).await.unwrap().into())
+			.unwrap()
+	};
+	let agent =
+		agent_events(futures::stream::iter([AgentEvent::FinalAnswer { content: "42".into() }]));
+
+	assert_eq!(
+		body(agent.into_response()).await,
+		"event: finalAnswer\ndata: {\"content\":\"42\",\"type\":\"finalAnswer\"}\n\n"
+	);
+
+	let api = api_events(futures::stream::iter([Ok(1), Err(Error::any("boom"))]));
+
+	assert_eq!(
+		body(api.into_response()).await,
+		"data: 1\n\nevent: error\ndata: boom\n\ndata: [DONE]\n\n"
+	);
+}
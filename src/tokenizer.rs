@@ -0,0 +1,187 @@
+//! Token counting with the BPE encodings of OpenAI models, backed by `tiktoken-rs`.
+//!
+//! Counts of messages include the per-message overhead of the chat format. Images, audio and
+//! files are not counted, tool calls and other input items are counted as their JSON, and items
+//! referenced by ID not at all.
+
+// crates.io
+use tiktoken_rs::CoreBPE;
+// self
+use crate::{
+	_prelude::*,
+	api::{
+		chat::{ChatMessage, ChatMessageContentMultimedia, ChatMessageContentText},
+		response::{ResponseInput, ResponseMessageInputContent},
+	},
+};
+
+/// Tokens wrapping every message, e.g. `<|start|>{role}\n{content}<|end|>\n`.
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Tokens taken by a message name on top of the name itself.
+const TOKENS_PER_NAME: usize = 1;
+/// Tokens priming the reply, `<|start|>assistant<|message|>`.
+const TOKENS_PER_REPLY: usize = 3;
+
+/// Count the tokens of `text` in the encoding of `model`.
+pub fn count_tokens(model: &Model, text: &str) -> usize {
+	encoding(model).encode_with_special_tokens(text).len()
+}
+
+/// Count the prompt tokens of `messages` sent to `model`, including the reply priming.
+pub fn count_chat_tokens(model: &Model, messages: &[ChatMessage]) -> usize {
+	messages
+		.iter()
+		.map(|m| {
+			let (role, name) = match m {
+				ChatMessage::Developer(m) => ("developer", m.name.as_deref()),
+				ChatMessage::System(m) => ("system", m.name.as_deref()),
+				ChatMessage::User(m) => ("user", m.name.as_deref()),
+				ChatMessage::Assistant(m) => ("assistant", m.common.name.as_deref()),
+				ChatMessage::Tool(_) => ("tool", None),
+			};
+
+			count_message(model, role, name, &chat_text(m))
+		})
+		.sum::<usize>()
+		+ TOKENS_PER_REPLY
+}
+
+/// Count the prompt tokens of `input` sent to `model`, including the reply priming.
+pub fn count_response_tokens(model: &Model, input: &[ResponseInput]) -> usize {
+	input
+		.iter()
+		.map(|i| match i {
+			ResponseInput::Message(m) => {
+				let text = match &m.content {
+					Either::A(text) => text.clone(),
+					Either::B(parts) => parts
+						.iter()
+						.filter_map(|p| match p {
+							ResponseMessageInputContent::InputText { text } => Some(text.as_str()),
+							_ => None,
+						})
+						.collect(),
+				};
+
+				count_message(model, m.role.as_str(), None, &text)
+			},
+			ResponseInput::Item(item) => TOKENS_PER_MESSAGE + count_tokens(model, &json(item)),
+			ResponseInput::ItemReference { .. } => 0,
+		})
+		.sum::<usize>()
+		+ TOKENS_PER_REPLY
+}
+
+fn count_message(model: &Model, role: &str, name: Option<&str>, text: &str) -> usize {
+	TOKENS_PER_MESSAGE
+		+ count_tokens(model, role)
+		+ count_tokens(model, text)
+		+ name.map_or(0, |n| TOKENS_PER_NAME + count_tokens(model, n))
+}
+
+fn chat_text(message: &ChatMessage) -> String {
+	let texts = |content: &Either<String, Vec<ChatMessageContentText>>| match content {
+		Either::A(text) => text.clone(),
+		Either::B(parts) => parts.iter().map(|p| p.text.as_str()).collect(),
+	};
+
+	match message {
+		ChatMessage::Developer(m) | ChatMessage::System(m) => texts(&m.content),
+		ChatMessage::User(m) => match &m.content {
+			Either::A(text) => text.clone(),
+			Either::B(parts) => parts
+				.iter()
+				.filter_map(|p| match p {
+					ChatMessageContentMultimedia::Text(text) => Some(text.as_str()),
+					_ => None,
+				})
+				.collect(),
+		},
+		ChatMessage::Assistant(m) => {
+			let mut text = match &m.common.content {
+				Either::A(text) => text.clone(),
+				Either::B(parts) => parts
+					.iter()
+					.map(|p| match p {
+						Either::A(p) => p.text.as_str(),
+						Either::B(p) => p.refusal.as_str(),
+					})
+					.collect(),
+			};
+
+			text.extend(m.refusal.as_deref());
+			text.extend(m.tool_calls.iter().flatten().map(json));
+
+			text
+		},
+		ChatMessage::Tool(m) => texts(&m.content),
+	}
+}
+
+fn json<T>(value: &T) -> String
+where
+	T: Serialize,
+{
+	serde_json::to_string(value).expect("serialization must succeed; qed")
+}
+
+fn encoding(model: &Model) -> &'static CoreBPE {
+	match model {
+		Model::Gpt4o
+		| Model::Gpt4oMini
+		| Model::Gpt41
+		| Model::Gpt41Mini
+		| Model::Gpt41Nano
+		| Model::O1
+		| Model::O3
+		| Model::O4Mini
+		| Model::Gpt5
+		| Model::Gpt5Mini
+		| Model::Gpt5Nano
+		| Model::GptImage1 => tiktoken_rs::o200k_base_singleton(),
+		_ => tiktoken_rs::cl100k_base_singleton(),
+	}
+}
+
+#[test]
+fn count_tokens_should_work() {
+	// self
+	use crate::api::{chat::ChatMessageCommon, response::ResponseMessage, r#type::Role};
+
+	assert_eq!(count_tokens(&Model::Gpt4o, "Hello, world!"), 4);
+	assert_eq!(count_tokens(&Model::TextEmbedding3Small, "Hello, world!"), 4);
+	assert_eq!(count_tokens(&Model::Gpt4o, ""), 0);
+
+	let messages = [
+		ChatMessage::System(ChatMessageCommon {
+			content: Either::A("Be brief.".into()),
+			name: None,
+		}),
+		ChatMessage::User(ChatMessageCommon {
+			content: Either::A("Hello, world!".into()),
+			name: Some("alice".into()),
+		}),
+	];
+	let expected = 2 * TOKENS_PER_MESSAGE
+		+ count_tokens(&Model::Gpt4o, "system")
+		+ count_tokens(&Model::Gpt4o, "Be brief.")
+		+ count_tokens(&Model::Gpt4o, "user")
+		+ 4 + TOKENS_PER_NAME
+		+ count_tokens(&Model::Gpt4o, "alice")
+		+ TOKENS_PER_REPLY;
+
+	assert_eq!(count_chat_tokens(&Model::Gpt4o, &messages), expected);
+
+	let input = [
+		ResponseInput::Message(ResponseMessage {
+			content: Either::A("Hello, world!".into()),
+			role: Role::User,
+		}),
+		ResponseInput::ItemReference { id: "foo".into() },
+	];
+
+	assert_eq!(
+		count_response_tokens(&Model::Gpt4o, &input),
+		TOKENS_PER_MESSAGE + count_tokens(&Model::Gpt4o, "user") + 4 + TOKENS_PER_REPLY
+	);
+}
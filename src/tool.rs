@@ -1,5 +1,16 @@
+//! Tools the agent can call.
+
+mod calculator;
+pub use calculator::*;
+
+mod fs;
+pub use fs::*;
+
+mod web_search;
+pub use web_search::*;
+
 // crates.io
-use futures::{future::BoxFuture, stream::BoxStream};
+use futures::{FutureExt, future::BoxFuture, stream::BoxStream};
 // self
 use crate::_prelude::*;
 
@@ -42,8 +53,128 @@ where
 	}
 }
 
+/// Call `tool` after checking `params` against its schema.
+///
+/// With the `jsonschema` feature, arguments violating [`ToolT::schema`] fail with
+/// [`ToolError::SchemaViolation`] without reaching the tool, so the violations can be fed back to
+/// the model. Without it, this is the same as [`ToolT::call`].
+pub fn call_validated(tool: &dyn ToolT, params: Value) -> BoxFuture<'static, Result<Value>> {
+	#[cfg(feature = "jsonschema")]
+	if let Err(e) = validate_arguments(tool, &params) {
+		return Box::pin(async move { Err(e) });
+	}
+
+	tool.call(params)
+}
+
+/// Stream `tool` after checking `params` against its schema, see [`call_validated`].
+pub fn call_stream_validated(
+	tool: &dyn ToolT,
+	params: Value,
+) -> BoxFuture<'static, Result<BoxStream<'static, String>>> {
+	#[cfg(feature = "jsonschema")]
+	if let Err(e) = validate_arguments(tool, &params) {
+		return Box::pin(async move { Err(e) });
+	}
+
+	tool.call_stream(params)
+}
+
+/// Check `params` against the schema of `tool`.
+#[cfg(feature = "jsonschema")]
+pub fn validate_arguments(tool: &dyn ToolT, params: &Value) -> Result<()> {
+	let validator = jsonschema::validator_for(&tool.schema())
+		.map_err(|e| Error::any(format!("invalid schema of tool '{}': {e}", tool.name())))?;
+	let errors = validator
+		.iter_errors(params)
+		.map(|e| ArgumentError { path: e.instance_path.to_string(), message: e.to_string() })
+		.collect::<Vec<_>>();
+
+	if errors.is_empty() {
+		Ok(())
+	} else {
+		Err(ToolError::SchemaViolation { tool: tool.name().into(), errors })?
+	}
+}
+
+/// Single schema violation of tool arguments.
+#[derive(Clone, Debug, Serialize)]
+pub struct ArgumentError {
+	/// JSON pointer to the offending value, empty for the root.
+	pub path: String,
+	/// What is wrong with the value.
+	pub message: String,
+}
+impl Display for ArgumentError {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		if self.path.is_empty() {
+			write!(f, "{}", self.message)
+		} else {
+			write!(f, "{}: {}", self.path, self.message)
+		}
+	}
+}
+
+/// Tool with typed parameters and output.
+///
+/// Every `TypedTool` is a [`ToolT`]: arguments are deserialized into `Params` before `call` runs,
+/// failing with [`ToolError::InvalidArguments`] on mismatch, and the output is serialized back to
+/// JSON.
+pub trait TypedTool
+where
+	Self: Send + Sync,
+{
+	/// Parameters the tool is called with.
+	type Params: DeserializeOwned;
+	/// Result of a successful call.
+	type Output: 'static + Serialize;
+
+	/// Unique identifier for the tool.
+	fn name(&self) -> &str;
+
+	/// Human-readable description of what the tool does.
+	fn description(&self) -> &str;
+
+	/// JSON schema describing `Params`.
+	fn schema(&self) -> Value;
+
+	/// Execute the tool with the deserialized parameters.
+	fn call(&self, params: Self::Params) -> BoxFuture<'static, Result<Self::Output>>;
+}
+impl<T> ToolT for T
+where
+	T: TypedTool,
+{
+	fn name(&self) -> &str {
+		TypedTool::name(self)
+	}
+
+	fn description(&self) -> &str {
+		TypedTool::description(self)
+	}
+
+	fn schema(&self) -> Value {
+		TypedTool::schema(self)
+	}
+
+	fn call(&self, params: Value) -> BoxFuture<'static, Result<Value>> {
+		match serde_json::from_value(params) {
+			Ok(params) =>
+				TypedTool::call(self, params).map(|res| Ok(serde_json::to_value(res?)?)).boxed(),
+			Err(e) => {
+				let e = ToolError::InvalidArguments {
+					tool: TypedTool::name(self).into(),
+					message: e.to_string(),
+				};
+
+				Box::pin(async move { Err(e)? })
+			},
+		}
+	}
+}
+
 /// Represents a request to call a tool with specific parameters.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolCall {
 	/// The name of the tool to call.
 	pub name: String,
@@ -52,31 +183,110 @@ pub struct ToolCall {
 }
 
 /// Represents the result of a tool call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ToolCallResult {
 	/// The tool call that was executed.
 	pub tool_call: ToolCall,
 	/// The outcome of the tool call, which can be either success or error.
 	pub outcome: ToolCallOutcome,
 }
-// impl ToolCallResult {
-// 	pub(crate) fn success(tool: String, args: Value, result: Value) -> Self {
-// 		Self {
-// 			tool_call: ToolCall { name: tool, args },
-// 			outcome: ToolCallOutcome::Success { result },
-// 		}
-// 	}
-
-// 	pub(crate) fn err(tool: String, args: Value, message: String) -> Self {
-// 		Self {
-// 			tool_call: ToolCall { name: tool, args },
-// 			outcome: ToolCallOutcome::Error { message },
-// 		}
-// 	}
-// }
-
-#[derive(Clone, Debug)]
+impl ToolCallResult {
+	pub(crate) fn success(tool: String, args: Value, result: Value) -> Self {
+		Self {
+			tool_call: ToolCall { name: tool, args },
+			outcome: ToolCallOutcome::Success { result },
+		}
+	}
+
+	pub(crate) fn err(tool: String, args: Value, message: String) -> Self {
+		Self {
+			tool_call: ToolCall { name: tool, args },
+			outcome: ToolCallOutcome::Error { message },
+		}
+	}
+}
+
+/// Outcome of a tool call.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolCallOutcome {
-	Success { result: Value },
-	Error { message: String },
+	/// The tool returned `result`.
+	Success {
+		/// Value returned by the tool.
+		result: Value,
+	},
+	/// The tool failed.
+	Error {
+		/// Error message fed back to the model.
+		message: String,
+	},
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn typed_tool_should_work() {
+	struct Add;
+	impl TypedTool for Add {
+		type Output = i64;
+		type Params = (i64, i64);
+
+		fn name(&self) -> &str {
+			"add"
+		}
+
+		fn description(&self) -> &str {
+			"Add two numbers."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({ "type": "array", "items": { "type": "integer" } })
+		}
+
+		fn call(&self, (a, b): Self::Params) -> BoxFuture<'static, Result<Self::Output>> {
+			Box::pin(async move { Ok(a + b) })
+		}
+	}
+
+	assert_eq!(ToolT::call(&Add, serde_json::json!([1, 2])).await.unwrap(), 3);
+	assert!(matches!(
+		ToolT::call(&Add, serde_json::json!("1")).await,
+		Err(Error::Tool(ToolError::InvalidArguments { .. }))
+	));
+}
+
+#[cfg(all(test, feature = "jsonschema"))]
+#[tokio::test]
+async fn call_validated_should_work() {
+	struct Echo;
+	impl ToolT for Echo {
+		fn name(&self) -> &str {
+			"echo"
+		}
+
+		fn description(&self) -> &str {
+			"Echo the text."
+		}
+
+		fn schema(&self) -> Value {
+			serde_json::json!({
+				"type": "object",
+				"properties": { "text": { "type": "string" } },
+				"required": ["text"],
+			})
+		}
+
+		fn call(&self, params: Value) -> BoxFuture<'static, Result<Value>> {
+			Box::pin(async move { Ok(params) })
+		}
+	}
+
+	assert!(call_validated(&Echo, serde_json::json!({ "text": "a" })).await.is_ok());
+
+	let Err(Error::Tool(ToolError::SchemaViolation { errors, .. })) =
+		call_validated(&Echo, serde_json::json!({ "text": 1 })).await
+	else {
+		panic!("arguments must be rejected");
+	};
+
+	assert_eq!(errors[0].path, "/text");
 }
@@ -0,0 +1,280 @@
+//! Arithmetic expression evaluation tool.
+
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::{_prelude::*, tool::TypedTool};
+
+/// Evaluates arithmetic expressions so the model does not have to do math in its head.
+///
+/// Supports `+ - * / % ^`, parentheses, the constants `pi` and `e` and the functions `abs`,
+/// `sqrt`, `exp`, `ln`, `log10`, `sin`, `cos`, `tan`, `floor`, `ceil`, `round`, `min` and `max`.
+/// Nothing but arithmetic is evaluated.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Calculator;
+impl TypedTool for Calculator {
+	type Output = f64;
+	type Params = CalculatorParams;
+
+	fn name(&self) -> &str {
+		"calculator"
+	}
+
+	fn description(&self) -> &str {
+		"Evaluate an arithmetic expression, e.g. `(3 + 4) * sqrt(2) ^ 2`. Supports + - * / % ^, \
+		parentheses, pi, e, abs, sqrt, exp, ln, log10, sin, cos, tan, floor, ceil, round, min and max."
+	}
+
+	fn schema(&self) -> Value {
+		serde_json::json!({
+			"type": "object",
+			"properties": { "expression": { "type": "string" } },
+			"required": ["expression"]
+		})
+	}
+
+	fn call(&self, params: Self::Params) -> BoxFuture<'static, Result<Self::Output>> {
+		let res = evaluate(&params.expression).map_err(|message| {
+			Error::Tool(ToolError::InvalidArguments { tool: "calculator".into(), message })
+		});
+
+		Box::pin(async move { res })
+	}
+}
+
+/// Parameters of a [`Calculator`] call.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CalculatorParams {
+	/// Expression to evaluate.
+	pub expression: String,
+}
+
+/// Evaluate `expression`, describing the problem on failure.
+pub fn evaluate(expression: &str) -> Result<f64, String> {
+	let mut parser = Parser {
+		chars: expression.chars().filter(|c| !c.is_whitespace()).collect(),
+		pos: 0,
+		depth: 0,
+	};
+	let value = parser.expr()?;
+
+	if let Some(c) = parser.peek() {
+		Err(format!("unexpected '{c}' at {}", parser.pos))?
+	}
+	if !value.is_finite() {
+		Err(format!("result is not finite: {value}"))?
+	}
+
+	Ok(value)
+}
+
+/// Recursive descent parser over the expression without whitespace.
+struct Parser {
+	chars: Vec<char>,
+	pos: usize,
+	depth: usize,
+}
+impl Parser {
+	/// Maximum nesting of operators, parentheses and calls, bounding the recursion.
+	const MAX_DEPTH: usize = 64;
+
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.pos).copied()
+	}
+
+	fn eat(&mut self, c: char) -> bool {
+		let hit = self.peek() == Some(c);
+
+		if hit {
+			self.pos += 1;
+		}
+
+		hit
+	}
+
+	// expr = term (("+" | "-") term)*
+	fn expr(&mut self) -> Result<f64, String> {
+		let mut value = self.term()?;
+
+		loop {
+			if self.eat('+') {
+				value += self.term()?;
+			} else if self.eat('-') {
+				value -= self.term()?;
+			} else {
+				return Ok(value);
+			}
+		}
+	}
+
+	// term = unary (("*" | "/" | "%") unary)*
+	fn term(&mut self) -> Result<f64, String> {
+		let mut value = self.unary()?;
+
+		loop {
+			if self.eat('*') {
+				value *= self.unary()?;
+			} else if self.eat('/') {
+				let divisor = self.unary()?;
+
+				if divisor == 0. {
+					Err("division by zero")?
+				}
+
+				value /= divisor;
+			} else if self.eat('%') {
+				let divisor = self.unary()?;
+
+				if divisor == 0. {
+					Err("division by zero")?
+				}
+
+				value %= divisor;
+			} else {
+				return Ok(value);
+			}
+		}
+	}
+
+	// unary = ("-" | "+") unary | power
+	fn unary(&mut self) -> Result<f64, String> {
+		self.depth += 1;
+
+		if self.depth > Self::MAX_DEPTH {
+			Err("expression is nested too deeply")?
+		}
+
+		let value = self.unary_inner();
+
+		self.depth -= 1;
+
+		value
+	}
+
+	fn unary_inner(&mut self) -> Result<f64, String> {
+		if self.eat('-') {
+			Ok(-self.unary()?)
+		} else if self.eat('+') {
+			self.unary()
+		} else {
+			self.power()
+		}
+	}
+
+	// power = atom ("^" unary)?, right associative
+	fn power(&mut self) -> Result<f64, String> {
+		let base = self.atom()?;
+
+		if self.eat('^') { Ok(base.powf(self.unary()?)) } else { Ok(base) }
+	}
+
+	// atom = number | "(" expr ")" | ident ("(" expr ("," expr)* ")")?
+	fn atom(&mut self) -> Result<f64, String> {
+		let start = self.pos;
+
+		match self.peek() {
+			Some('(') => {
+				self.pos += 1;
+
+				let value = self.expr()?;
+
+				if !self.eat(')') {
+					Err(format!("expected ')' at {}", self.pos))?
+				}
+
+				Ok(value)
+			},
+			Some(c) if c.is_ascii_digit() || c == '.' => {
+				while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+					self.pos += 1;
+				}
+				// Exponent, e.g. `1e-3`.
+				if self.peek() == Some('e')
+					&& self
+						.chars
+						.get(self.pos + 1)
+						.is_some_and(|c| c.is_ascii_digit() || *c == '-' || *c == '+')
+				{
+					self.pos += 2;
+
+					while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+						self.pos += 1;
+					}
+				}
+
+				let number = self.chars[start..self.pos].iter().collect::<String>();
+
+				number.parse().map_err(|_| format!("invalid number '{number}'"))
+			},
+			Some(c) if c.is_ascii_alphabetic() => {
+				while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+					self.pos += 1;
+				}
+
+				let ident = self.chars[start..self.pos].iter().collect::<String>();
+
+				if !self.eat('(') {
+					return match ident.as_str() {
+						"pi" => Ok(std::f64::consts::PI),
+						"e" => Ok(std::f64::consts::E),
+						_ => Err(format!("unknown constant '{ident}'")),
+					};
+				}
+
+				let mut args = vec![self.expr()?];
+
+				while self.eat(',') {
+					args.push(self.expr()?);
+				}
+
+				if !self.eat(')') {
+					Err(format!("expected ')' at {}", self.pos))?
+				}
+
+				call(&ident, &args)
+			},
+			Some(c) => Err(format!("unexpected '{c}' at {start}")),
+			None => Err("unexpected end of expression".into()),
+		}
+	}
+}
+
+fn call(function: &str, args: &[f64]) -> Result<f64, String> {
+	let f = match (function, args) {
+		("min", [_, ..]) => return Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+		("max", [_, ..]) => return Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+		(_, [_]) => match function {
+			"abs" => f64::abs,
+			"sqrt" => f64::sqrt,
+			"exp" => f64::exp,
+			"ln" => f64::ln,
+			"log10" => f64::log10,
+			"sin" => f64::sin,
+			"cos" => f64::cos,
+			"tan" => f64::tan,
+			"floor" => f64::floor,
+			"ceil" => f64::ceil,
+			"round" => f64::round,
+			_ => Err(format!("unknown function '{function}'"))?,
+		},
+		_ => Err(format!("wrong number of arguments for '{function}'"))?,
+	};
+
+	Ok(f(args[0]))
+}
+
+#[test]
+fn evaluate_should_work() {
+	assert_eq!(evaluate("1 + 2 * 3").unwrap(), 7.);
+	assert_eq!(evaluate("(1 + 2) * 3").unwrap(), 9.);
+	assert_eq!(evaluate("-2 ^ 2").unwrap(), -4.);
+	assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.);
+	assert_eq!(evaluate("10 % 4 - 1.5e1").unwrap(), -13.);
+	assert_eq!(evaluate("max(1, sqrt(16), 3) + abs(-1)").unwrap(), 5.);
+	assert_eq!(evaluate("round(pi * 100)").unwrap(), 314.);
+	assert!(evaluate("1 / 0").is_err());
+	assert!(evaluate("1 +").is_err());
+	assert!(evaluate("system(1)").is_err());
+	assert!(evaluate("(1").is_err());
+	assert!(evaluate(&format!("{}1{}", "(".repeat(100), ")".repeat(100))).is_err());
+}
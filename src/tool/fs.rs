@@ -0,0 +1,331 @@
+//! File system tool confined to a root directory.
+
+// std
+use std::path::{Component, Path, PathBuf};
+// crates.io
+use futures::future::BoxFuture;
+use tokio::fs;
+// self
+use crate::{_prelude::*, tool::TypedTool};
+
+/// Reads, writes, lists and globs files below a root directory.
+///
+/// Paths are relative to the root. Absolute paths, `..` and symlinks leading out of the root are
+/// refused with [`ToolError::PathOutsideRoot`].
+#[derive(Clone, Debug)]
+pub struct FsTool {
+	root: PathBuf,
+	read_only: bool,
+}
+impl FsTool {
+	/// Maximum number of paths returned by a glob.
+	pub const MAX_MATCHES: usize = 1_000;
+
+	/// Create a tool confined to `root`, which must exist.
+	pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+		Ok(Self { root: std::fs::canonicalize(root)?, read_only: false })
+	}
+
+	/// Refuse writes.
+	pub fn read_only(mut self) -> Self {
+		self.read_only = true;
+
+		self
+	}
+}
+impl TypedTool for FsTool {
+	type Output = Value;
+	type Params = FsParams;
+
+	fn name(&self) -> &str {
+		"fs"
+	}
+
+	fn description(&self) -> &str {
+		if self.read_only {
+			"Read files, list directories and glob paths (e.g. `src/**/*.rs`) in the workspace. \
+			Paths are relative to the workspace root."
+		} else {
+			"Read and write files, list directories and glob paths (e.g. `src/**/*.rs`) in the \
+			workspace. Paths are relative to the workspace root."
+		}
+	}
+
+	fn schema(&self) -> Value {
+		let ops = if self.read_only {
+			&["read", "list", "glob"][..]
+		} else {
+			&["read", "write", "list", "glob"]
+		};
+
+		serde_json::json!({
+			"type": "object",
+			"properties": {
+				"op": { "type": "string", "enum": ops },
+				"path": { "type": "string", "description": "Path for `read`, `write` and `list`." },
+				"content": { "type": "string", "description": "File content for `write`." },
+				"pattern": { "type": "string", "description": "Pattern for `glob`." }
+			},
+			"required": ["op"]
+		})
+	}
+
+	fn call(&self, params: Self::Params) -> BoxFuture<'static, Result<Self::Output>> {
+		let tool = self.clone();
+
+		Box::pin(async move {
+			match params {
+				FsParams::Read { path } => {
+					let path = tool.resolve(&path).await?;
+
+					Ok(Value::String(fs::read_to_string(path).await?))
+				},
+				FsParams::Write { path, content } => {
+					if tool.read_only {
+						Err(ToolError::InvalidArguments {
+							tool: "fs".into(),
+							message: "writing is disabled".into(),
+						})?
+					}
+
+					let path = tool.resolve(&path).await?;
+
+					if let Some(parent) = path.parent() {
+						fs::create_dir_all(parent).await?;
+					}
+
+					fs::write(&path, &content).await?;
+
+					Ok(serde_json::json!({ "written": content.len() }))
+				},
+				FsParams::List { path } => {
+					let path = tool.resolve(path.as_deref().unwrap_or(".")).await?;
+					let mut dir = fs::read_dir(path).await?;
+					let mut entries = Vec::new();
+
+					while let Some(entry) = dir.next_entry().await? {
+						let mut name = entry.file_name().to_string_lossy().into_owned();
+
+						if entry.file_type().await?.is_dir() {
+							name.push('/');
+						}
+
+						entries.push(name);
+					}
+
+					entries.sort();
+
+					Ok(serde_json::to_value(entries)?)
+				},
+				FsParams::Glob { pattern } => Ok(serde_json::to_value(tool.glob(&pattern).await?)?),
+			}
+		})
+	}
+}
+impl FsTool {
+	/// Map `path` into the root, refusing anything which could leave it.
+	async fn resolve(&self, path: &str) -> Result<PathBuf> {
+		let outside = || ToolError::PathOutsideRoot(path.into());
+		let mut resolved = self.root.clone();
+		let mut exists = true;
+
+		for component in Path::new(path).components() {
+			match component {
+				Component::Normal(c) => resolved.push(c),
+				Component::CurDir => continue,
+				_ => Err(outside())?,
+			}
+
+			if !exists {
+				continue;
+			}
+
+			// Symlinks may point elsewhere, and dangling ones would be followed on write.
+			match fs::symlink_metadata(&resolved).await {
+				Ok(meta) if meta.file_type().is_symlink() =>
+					match fs::canonicalize(&resolved).await {
+						Ok(real) if real.starts_with(&self.root) => (),
+						_ => Err(outside())?,
+					},
+				Ok(_) => (),
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => exists = false,
+				Err(e) => Err(e)?,
+			}
+		}
+
+		Ok(resolved)
+	}
+
+	/// Paths below the root matching `pattern`, sorted.
+	async fn glob(&self, pattern: &str) -> Result<Vec<String>> {
+		let pattern = pattern.trim_start_matches("./").split('/').collect::<Vec<_>>();
+		let mut dirs = vec![self.root.clone()];
+		let mut matches = Vec::new();
+
+		while let Some(dir) = dirs.pop() {
+			let mut entries = fs::read_dir(&dir).await?;
+
+			while let Some(entry) = entries.next_entry().await? {
+				let path = entry.path();
+				let Ok(relative) = path.strip_prefix(&self.root) else { continue };
+				let relative = relative.to_string_lossy().replace('\\', "/");
+
+				// Symlinked directories are not followed, they may lead out of the root.
+				if entry.file_type().await?.is_dir() {
+					dirs.push(path);
+				}
+				if glob_match(&pattern, &relative.split('/').collect::<Vec<_>>()) {
+					matches.push(relative);
+
+					if matches.len() == Self::MAX_MATCHES {
+						dirs.clear();
+
+						break;
+					}
+				}
+			}
+		}
+
+		matches.sort();
+
+		Ok(matches)
+	}
+}
+
+/// Operation of a [`FsTool`] call.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum FsParams {
+	Read { path: String },
+	Write { path: String, content: String },
+	List { path: Option<String> },
+	Glob { pattern: String },
+}
+
+/// Match path segments against pattern segments supporting `*`, `?` and `**`.
+fn glob_match(pattern: &[&str], path: &[&str]) -> bool {
+	wildcard_match(
+		pattern,
+		path,
+		|p| *p == "**",
+		|p, s| {
+			wildcard_match(
+				&p.chars().collect::<Vec<_>>(),
+				&s.chars().collect::<Vec<_>>(),
+				|p| *p == '*',
+				|p, c| *p == '?' || p == c,
+			)
+		},
+	)
+}
+
+/// Match `items` against `pattern`, where stars match any run of items.
+///
+/// Only the latest star is backtracked to, which keeps the match polynomial for any pattern.
+fn wildcard_match<P, T>(
+	pattern: &[P],
+	items: &[T],
+	is_star: impl Fn(&P) -> bool,
+	item_match: impl Fn(&P, &T) -> bool,
+) -> bool {
+	let (mut p, mut i) = (0, 0);
+	// Pattern position after the latest star and the item it resumes matching at.
+	let mut backtrack = None;
+
+	while i < items.len() {
+		if p < pattern.len() && is_star(&pattern[p]) {
+			p += 1;
+			backtrack = Some((p, i));
+		} else if p < pattern.len() && item_match(&pattern[p], &items[i]) {
+			p += 1;
+			i += 1;
+		} else if let Some((star_p, star_i)) = backtrack {
+			// Let the star swallow one more item.
+			(p, i) = (star_p, star_i + 1);
+			backtrack = Some((star_p, star_i + 1));
+		} else {
+			return false;
+		}
+	}
+
+	pattern[p..].iter().all(is_star)
+}
+
+#[test]
+fn glob_match_should_work() {
+	let glob = |pattern: &str, path: &str| {
+		glob_match(&pattern.split('/').collect::<Vec<_>>(), &path.split('/').collect::<Vec<_>>())
+	};
+
+	assert!(glob("**/*.rs", "a.rs"));
+	assert!(glob("**/*.rs", "src/tool/fs.rs"));
+	assert!(glob("src/**/f?.rs", "src/tool/fs.rs"));
+	assert!(glob("src/**", "src"));
+	assert!(!glob("src/*.rs", "src/tool/fs.rs"));
+	assert!(!glob("*.rs", "a.txt"));
+
+	// Would take ages with naive backtracking.
+	let pattern = format!("{}b", "*a".repeat(30));
+	let path = "a".repeat(100);
+
+	assert!(!glob(&pattern, &path));
+	assert!(!glob(&format!("{}b", "**/".repeat(30)), &"a/".repeat(100)));
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn fs_tool_should_work() {
+	let dir = std::env::temp_dir().join(format!("openagent-{}", uuid::Uuid::new_v4()));
+
+	std::fs::create_dir_all(&dir).unwrap();
+
+	let tool = FsTool::new(&dir).unwrap();
+	let call = |params: Value| crate::tool::ToolT::call(&tool, params);
+
+	call(serde_json::json!({ "op": "write", "path": "src/a.rs", "content": "a" })).await.unwrap();
+	call(serde_json::json!({ "op": "write", "path": "b.txt", "content": "b" })).await.unwrap();
+
+	assert_eq!(call(serde_json::json!({ "op": "read", "path": "./src/a.rs" })).await.unwrap(), "a");
+	assert_eq!(
+		call(serde_json::json!({ "op": "list" })).await.unwrap(),
+		serde_json::json!(["b.txt", "src/"])
+	);
+	assert_eq!(
+		call(serde_json::json!({ "op": "glob", "pattern": "**/*.rs" })).await.unwrap(),
+		serde_json::json!(["src/a.rs"])
+	);
+
+	for path in ["../x", "/etc/passwd", "src/../../x"] {
+		assert!(matches!(
+			call(serde_json::json!({ "op": "read", "path": path })).await,
+			Err(Error::Tool(ToolError::PathOutsideRoot(_)))
+		));
+	}
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::symlink;
+
+		let outside = dir.with_extension("outside");
+
+		symlink(&outside, dir.join("dangling")).unwrap();
+		symlink(dir.join("src"), dir.join("inside")).unwrap();
+
+		// Writing through the dangling link would create a file outside of the root.
+		for path in ["dangling", "dangling/x"] {
+			assert!(matches!(
+				call(serde_json::json!({ "op": "write", "path": path, "content": "x" })).await,
+				Err(Error::Tool(ToolError::PathOutsideRoot(_)))
+			));
+		}
+
+		assert!(!outside.exists());
+		assert_eq!(
+			call(serde_json::json!({ "op": "read", "path": "inside/a.rs" })).await.unwrap(),
+			"a"
+		);
+	}
+
+	let _ = std::fs::remove_dir_all(dir);
+}
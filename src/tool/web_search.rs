@@ -0,0 +1,219 @@
+//! Provider-agnostic stand-in for the hosted `web_search` tool.
+
+// std
+use std::{fmt::Debug, sync::Arc};
+// crates.io
+use futures::future::BoxFuture;
+// self
+use crate::{
+	_prelude::*,
+	api::response::{Location, SearchContextSize, Tool, WebSearchFilters},
+	tool::TypedTool,
+};
+
+/// Search engine behind a [`WebSearchTool`].
+///
+/// A closure `Fn(SearchQuery) -> impl Future<Output = Result<Vec<SearchResult>>>` is a backend.
+pub trait SearchBackend
+where
+	Self: Send + Sync,
+{
+	/// Run `query`.
+	fn search(&self, query: SearchQuery) -> BoxFuture<'static, Result<Vec<SearchResult>>>;
+}
+impl<F, Fut> SearchBackend for F
+where
+	F: Send + Sync + Fn(SearchQuery) -> Fut,
+	Fut: 'static + Send + Future<Output = Result<Vec<SearchResult>>>,
+{
+	fn search(&self, query: SearchQuery) -> BoxFuture<'static, Result<Vec<SearchResult>>> {
+		Box::pin(self(query))
+	}
+}
+
+/// Query handed to a [`SearchBackend`].
+#[derive(Clone, Debug)]
+pub struct SearchQuery {
+	/// Search terms.
+	pub query: String,
+	/// Maximum number of results wanted.
+	pub max_results: usize,
+	/// Domains results must come from, any if `None`.
+	pub allowed_domains: Option<Vec<String>>,
+	/// Approximate location of the user.
+	pub user_location: Option<Location>,
+}
+
+/// Single hit of a web search.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchResult {
+	/// Page title.
+	pub title: String,
+	/// Page URL.
+	pub url: String,
+	/// Relevant excerpt of the page.
+	pub snippet: String,
+}
+
+/// Web search tool running through a [`SearchBackend`].
+///
+/// Mirrors the Responses API's hosted [`Tool::WebSearch`], so agents keep the same toolset on
+/// providers without hosted tools.
+#[derive(Clone)]
+pub struct WebSearchTool {
+	backend: Arc<dyn SearchBackend>,
+	max_results: usize,
+	filters: Option<WebSearchFilters>,
+	user_location: Option<Location>,
+}
+impl WebSearchTool {
+	/// Create a tool searching through `backend` with a medium context size.
+	pub fn new<B>(backend: B) -> Self
+	where
+		B: 'static + SearchBackend,
+	{
+		Self { backend: Arc::new(backend), max_results: 5, filters: None, user_location: None }
+	}
+
+	/// Create a tool configured like the hosted `tool`, `None` if it is no web search tool.
+	pub fn from_hosted<B>(tool: &Tool, backend: B) -> Option<Self>
+	where
+		B: 'static + SearchBackend,
+	{
+		let (filters, search_context_size, user_location) = match tool {
+			Tool::WebSearch { filters, search_context_size, user_location } =>
+				(filters.clone(), search_context_size, user_location),
+			Tool::WebSearchPreview { search_context_size, user_location } =>
+				(None, search_context_size, user_location),
+			_ => return None,
+		};
+		let max_results = match search_context_size {
+			Some(SearchContextSize::Low) => 3,
+			Some(SearchContextSize::High) => 10,
+			_ => 5,
+		};
+
+		Some(Self {
+			backend: Arc::new(backend),
+			max_results,
+			filters,
+			user_location: user_location.clone(),
+		})
+	}
+
+	/// Set the maximum number of results per search.
+	pub fn max_results(mut self, max_results: usize) -> Self {
+		self.max_results = max_results;
+
+		self
+	}
+
+	/// Only keep results from `domains` and their subdomains.
+	pub fn allowed_domains(mut self, domains: Vec<String>) -> Self {
+		self.filters = Some(WebSearchFilters { allowed_domains: Some(domains) });
+
+		self
+	}
+}
+impl Debug for WebSearchTool {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		f.debug_struct("WebSearchTool")
+			.field("max_results", &self.max_results)
+			.field("filters", &self.filters)
+			.field("user_location", &self.user_location)
+			.finish_non_exhaustive()
+	}
+}
+impl TypedTool for WebSearchTool {
+	type Output = Vec<SearchResult>;
+	type Params = WebSearchParams;
+
+	fn name(&self) -> &str {
+		"web_search"
+	}
+
+	fn description(&self) -> &str {
+		"Search the web for up-to-date information. Returns titles, URLs and snippets."
+	}
+
+	fn schema(&self) -> Value {
+		serde_json::json!({
+			"type": "object",
+			"properties": { "query": { "type": "string" } },
+			"required": ["query"]
+		})
+	}
+
+	fn call(&self, params: Self::Params) -> BoxFuture<'static, Result<Self::Output>> {
+		let allowed_domains = self.filters.as_ref().and_then(|f| f.allowed_domains.clone());
+		let max_results = self.max_results;
+		let search = self.backend.search(SearchQuery {
+			query: params.query,
+			max_results,
+			allowed_domains: allowed_domains.clone(),
+			user_location: self.user_location.clone(),
+		});
+
+		Box::pin(async move {
+			let mut results = search.await?;
+
+			// Backends may ignore the hints, enforce them.
+			if let Some(domains) = allowed_domains {
+				results.retain(|r| domain_allowed(&r.url, &domains));
+			}
+
+			results.truncate(max_results);
+
+			Ok(results)
+		})
+	}
+}
+
+/// Parameters of a [`WebSearchTool`] call.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WebSearchParams {
+	/// Search terms.
+	pub query: String,
+}
+
+fn domain_allowed(url: &str, domains: &[String]) -> bool {
+	let host = url.split_once("://").map_or(url, |(_, rest)| rest);
+	let host = host.split(['/', '?', '#']).next().unwrap_or_default();
+	let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+	let host = host.split(':').next().unwrap_or_default().to_ascii_lowercase();
+
+	domains.iter().any(|d| {
+		let d = d.to_ascii_lowercase();
+
+		host == d || host.strip_suffix(&d).is_some_and(|sub| sub.ends_with('.'))
+	})
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn web_search_tool_should_work() {
+	let backend = |query: SearchQuery| async move {
+		Ok(["https://docs.rs/a", "https://evil.com/rs.io", "http://x.crates.io:80/b"]
+			.into_iter()
+			.map(|url| SearchResult {
+				title: query.query.clone(),
+				url: url.into(),
+				snippet: "".into(),
+			})
+			.collect())
+	};
+	let hosted = Tool::WebSearch {
+		filters: Some(WebSearchFilters {
+			allowed_domains: Some(vec!["docs.rs".into(), "crates.io".into()]),
+		}),
+		search_context_size: Some(SearchContextSize::Low),
+		user_location: None,
+	};
+	let tool = WebSearchTool::from_hosted(&hosted, backend).unwrap();
+	let results = TypedTool::call(&tool, WebSearchParams { query: "rust".into() }).await.unwrap();
+
+	assert_eq!(
+		results.iter().map(|r| r.url.as_str()).collect::<Vec<_>>(),
+		["https://docs.rs/a", "http://x.crates.io:80/b"]
+	);
+}
@@ -5,7 +5,10 @@ use std::sync::LazyLock;
 // crates.io
 use regex::Regex;
 // self
-use crate::_prelude::*;
+use crate::{_prelude::*, http::Pricing};
+
+mod registry;
+pub use registry::*;
 
 /// Regex pattern for removing date suffixes from model identifiers
 static RE_DATE_SUFFIX: LazyLock<Regex> =
@@ -19,6 +22,32 @@ pub enum Model {
 	Gpt4o,
 	/// OpenAI's GPT-4o Mini model, a smaller version of GPT-4o
 	Gpt4oMini,
+	/// OpenAI's GPT-4.1 model with a long context window
+	Gpt41,
+	/// OpenAI's GPT-4.1 Mini model, a smaller version of GPT-4.1
+	Gpt41Mini,
+	/// OpenAI's GPT-4.1 Nano model, the fastest version of GPT-4.1
+	Gpt41Nano,
+	/// OpenAI's o1 reasoning model
+	O1,
+	/// OpenAI's o3 reasoning model
+	O3,
+	/// OpenAI's o4-mini reasoning model, a smaller successor of o3
+	O4Mini,
+	/// OpenAI's GPT-5 reasoning model
+	Gpt5,
+	/// OpenAI's GPT-5 Mini model, a smaller version of GPT-5
+	Gpt5Mini,
+	/// OpenAI's GPT-5 Nano model, the fastest version of GPT-5
+	Gpt5Nano,
+	/// OpenAI's GPT Image 1 image generation model
+	GptImage1,
+	/// OpenAI's Whisper speech recognition model
+	Whisper1,
+	/// OpenAI's TTS 1 text-to-speech model
+	Tts1,
+	/// OpenAI's TTS 1 HD text-to-speech model, optimized for quality
+	Tts1Hd,
 	/// OpenAI's small text embedding model for vector representations
 	TextEmbedding3Small,
 	/// OpenAI's large text embedding model for higher quality vectors
@@ -43,14 +72,30 @@ pub enum Model {
 }
 impl Model {
 	/// Creates a Model instance from a string identifier
+	///
+	/// IDs of no built-in model are looked up in the installed [`ModelRegistry`].
 	pub fn from_id(id: &str) -> Self {
 		match id {
 			"gpt-4o" => Self::Gpt4o,
 			"gpt-4o-mini" => Self::Gpt4oMini,
+			"gpt-4.1" => Self::Gpt41,
+			"gpt-4.1-mini" => Self::Gpt41Mini,
+			"gpt-4.1-nano" => Self::Gpt41Nano,
+			"o1" => Self::O1,
+			"o3" => Self::O3,
+			"o4-mini" => Self::O4Mini,
+			"gpt-5" => Self::Gpt5,
+			"gpt-5-mini" => Self::Gpt5Mini,
+			"gpt-5-nano" => Self::Gpt5Nano,
+			"gpt-image-1" => Self::GptImage1,
+			"whisper-1" => Self::Whisper1,
+			"tts-1" => Self::Tts1,
+			"tts-1-hd" => Self::Tts1Hd,
 			"text-embedding-3-small" => Self::TextEmbedding3Small,
 			"text-embedding-3-large" => Self::TextEmbedding3Large,
 			"text-embedding-ada-002" => Self::TextEmbeddingAda002,
-			_ => Self::Unknown(id.to_owned()),
+			_ => ModelRegistry::lookup(id)
+				.map_or_else(|| Self::Unknown(id.to_owned()), |info| info.model()),
 		}
 	}
 
@@ -59,6 +104,19 @@ impl Model {
 		match self {
 			Self::Gpt4o => Cow::Borrowed("gpt-4o"),
 			Self::Gpt4oMini => Cow::Borrowed("gpt-4o-mini"),
+			Self::Gpt41 => Cow::Borrowed("gpt-4.1"),
+			Self::Gpt41Mini => Cow::Borrowed("gpt-4.1-mini"),
+			Self::Gpt41Nano => Cow::Borrowed("gpt-4.1-nano"),
+			Self::O1 => Cow::Borrowed("o1"),
+			Self::O3 => Cow::Borrowed("o3"),
+			Self::O4Mini => Cow::Borrowed("o4-mini"),
+			Self::Gpt5 => Cow::Borrowed("gpt-5"),
+			Self::Gpt5Mini => Cow::Borrowed("gpt-5-mini"),
+			Self::Gpt5Nano => Cow::Borrowed("gpt-5-nano"),
+			Self::GptImage1 => Cow::Borrowed("gpt-image-1"),
+			Self::Whisper1 => Cow::Borrowed("whisper-1"),
+			Self::Tts1 => Cow::Borrowed("tts-1"),
+			Self::Tts1Hd => Cow::Borrowed("tts-1-hd"),
 			Self::TextEmbedding3Small => Cow::Borrowed("text-embedding-3-small"),
 			Self::TextEmbedding3Large => Cow::Borrowed("text-embedding-3-large"),
 			Self::TextEmbeddingAda002 => Cow::Borrowed("text-embedding-ada-002"),
@@ -72,6 +130,19 @@ impl Model {
 		match self {
 			Self::Gpt4o => Cow::Borrowed("GPT-4o"),
 			Self::Gpt4oMini => Cow::Borrowed("GPT-4o Mini"),
+			Self::Gpt41 => Cow::Borrowed("GPT-4.1"),
+			Self::Gpt41Mini => Cow::Borrowed("GPT-4.1 Mini"),
+			Self::Gpt41Nano => Cow::Borrowed("GPT-4.1 Nano"),
+			Self::O1 => Cow::Borrowed("o1"),
+			Self::O3 => Cow::Borrowed("o3"),
+			Self::O4Mini => Cow::Borrowed("o4-mini"),
+			Self::Gpt5 => Cow::Borrowed("GPT-5"),
+			Self::Gpt5Mini => Cow::Borrowed("GPT-5 Mini"),
+			Self::Gpt5Nano => Cow::Borrowed("GPT-5 Nano"),
+			Self::GptImage1 => Cow::Borrowed("GPT Image 1"),
+			Self::Whisper1 => Cow::Borrowed("Whisper 1"),
+			Self::Tts1 => Cow::Borrowed("TTS 1"),
+			Self::Tts1Hd => Cow::Borrowed("TTS 1 HD"),
 			Self::TextEmbedding3Small => Cow::Borrowed("Text Embedding 3 Small"),
 			Self::TextEmbedding3Large => Cow::Borrowed("Text Embedding 3 Large"),
 			Self::TextEmbeddingAda002 => Cow::Borrowed("Text Embedding Ada 002"),
@@ -83,35 +154,154 @@ impl Model {
 	/// Determines if this model supports text embedding operations
 	pub const fn embedding(&self) -> bool {
 		match self {
-			Self::Gpt4o | Self::Gpt4oMini => false,
 			Self::TextEmbedding3Small | Self::TextEmbedding3Large | Self::TextEmbeddingAda002 =>
 				true,
 			Self::Custom { embedding, .. } => *embedding,
-			Self::Unknown(_) => false,
+			_ => false,
 		}
 	}
 
 	/// Determines if this model supports reasoning capabilities
 	pub const fn reasoning(&self) -> bool {
+		match self {
+			Self::O1 | Self::O3 | Self::O4Mini | Self::Gpt5 | Self::Gpt5Mini | Self::Gpt5Nano =>
+				true,
+			Self::Custom { reasoning, .. } => *reasoning,
+			_ => false,
+		}
+	}
+
+	/// Returns the maximum number of tokens this model can generate per response, if known
+	pub fn max_output_tokens(&self) -> Option<u32> {
+		match self {
+			Self::Gpt4o | Self::Gpt4oMini => Some(16_384),
+			Self::Gpt41 | Self::Gpt41Mini | Self::Gpt41Nano => Some(32_768),
+			Self::O1 | Self::O3 | Self::O4Mini => Some(100_000),
+			Self::Gpt5 | Self::Gpt5Mini | Self::Gpt5Nano => Some(128_000),
+			Self::Custom { id, .. } => ModelRegistry::lookup(id)?.max_output_tokens,
+			_ => None,
+		}
+	}
+
+	/// Returns the maximum number of input and output tokens this model handles per request, if
+	/// known
+	pub fn context_window(&self) -> Option<u32> {
+		match self {
+			Self::Gpt4o | Self::Gpt4oMini => Some(128_000),
+			Self::Gpt41 | Self::Gpt41Mini | Self::Gpt41Nano => Some(1_047_576),
+			Self::O1 | Self::O3 | Self::O4Mini => Some(200_000),
+			Self::Gpt5 | Self::Gpt5Mini | Self::Gpt5Nano => Some(400_000),
+			Self::TextEmbedding3Small | Self::TextEmbedding3Large | Self::TextEmbeddingAda002 =>
+				Some(8_191),
+			Self::Custom { id, .. } => ModelRegistry::lookup(id)?.context_window,
+			_ => None,
+		}
+	}
+
+	/// Returns the token prices of this model, if known
+	///
+	/// Prices registered in the installed [`ModelRegistry`] take precedence over the built-in list
+	/// prices in USD.
+	pub fn pricing(&self) -> Option<Pricing> {
+		if let Some(pricing) = ModelRegistry::lookup(&self.id()).and_then(|info| info.pricing) {
+			return Some(pricing);
+		}
+
+		let (input, cached_input, output) = match self {
+			Self::Gpt4o => (2.5, 1.25, 10.),
+			Self::Gpt4oMini => (0.15, 0.075, 0.6),
+			Self::Gpt41 | Self::O3 => (2., 0.5, 8.),
+			Self::Gpt41Mini => (0.4, 0.1, 1.6),
+			Self::Gpt41Nano => (0.1, 0.025, 0.4),
+			Self::O1 => (15., 7.5, 60.),
+			Self::O4Mini => (1.1, 0.275, 4.4),
+			Self::Gpt5 => (1.25, 0.125, 10.),
+			Self::Gpt5Mini => (0.25, 0.025, 2.),
+			Self::Gpt5Nano => (0.05, 0.005, 0.4),
+			Self::TextEmbedding3Small => (0.02, 0.02, 0.),
+			Self::TextEmbedding3Large => (0.13, 0.13, 0.),
+			Self::TextEmbeddingAda002 => (0.1, 0.1, 0.),
+			_ => return None,
+		};
+
+		Some(Pricing::new(input, output).cached_input(cached_input))
+	}
+
+	/// Returns the modalities this model accepts as input, text only if unknown
+	pub const fn input_modalities(&self) -> &'static [Modality] {
 		match self {
 			Self::Gpt4o
 			| Self::Gpt4oMini
-			| Self::TextEmbedding3Small
-			| Self::TextEmbedding3Large
-			| Self::TextEmbeddingAda002 => false,
-			Self::Custom { reasoning, .. } => *reasoning,
-			Self::Unknown(_) => false,
+			| Self::Gpt41
+			| Self::Gpt41Mini
+			| Self::Gpt41Nano
+			| Self::O1
+			| Self::O3
+			| Self::O4Mini
+			| Self::Gpt5
+			| Self::Gpt5Mini
+			| Self::Gpt5Nano
+			| Self::GptImage1 => &[Modality::Text, Modality::Image],
+			Self::Whisper1 => &[Modality::Audio],
+			_ => &[Modality::Text],
+		}
+	}
+
+	/// Returns the modalities this model generates, empty for embedding models
+	pub const fn output_modalities(&self) -> &'static [Modality] {
+		match self {
+			Self::GptImage1 => &[Modality::Image],
+			Self::Tts1 | Self::Tts1Hd => &[Modality::Audio],
+			Self::TextEmbedding3Small | Self::TextEmbedding3Large | Self::TextEmbeddingAda002 =>
+				&[],
+			Self::Custom { embedding: true, .. } => &[],
+			_ => &[Modality::Text],
+		}
+	}
+
+	/// Determines if this model accepts a sampling temperature
+	pub const fn temperature(&self) -> bool {
+		match self {
+			Self::Gpt4o
+			| Self::Gpt4oMini
+			| Self::Gpt41
+			| Self::Gpt41Mini
+			| Self::Gpt41Nano
+			| Self::Whisper1
+			| Self::Unknown(_) => true,
+			Self::Custom { embedding, reasoning, .. } => !*embedding && !*reasoning,
+			_ => false,
+		}
+	}
+
+	/// Returns the date of this model's training data cutoff as `YYYY-MM-DD`, if known
+	pub const fn knowledge_cutoff(&self) -> Option<&'static str> {
+		match self {
+			Self::Gpt4o | Self::Gpt4oMini | Self::O1 => Some("2023-10-01"),
+			Self::Gpt41 | Self::Gpt41Mini | Self::Gpt41Nano | Self::O3 | Self::O4Mini =>
+				Some("2024-06-01"),
+			Self::Gpt5 => Some("2024-09-30"),
+			Self::Gpt5Mini | Self::Gpt5Nano => Some("2024-05-31"),
+			_ => None,
 		}
 	}
 
 	/// Determines if this model supports function calling features
 	pub const fn function_calling(&self) -> bool {
 		match self {
-			Self::Gpt4o | Self::Gpt4oMini => true,
-			Self::TextEmbedding3Small | Self::TextEmbedding3Large | Self::TextEmbeddingAda002 =>
-				false,
+			Self::Gpt4o
+			| Self::Gpt4oMini
+			| Self::Gpt41
+			| Self::Gpt41Mini
+			| Self::Gpt41Nano
+			| Self::O1
+			| Self::O3
+			| Self::O4Mini
+			| Self::Gpt5
+			| Self::Gpt5Mini
+			| Self::Gpt5Nano => true,
 			Self::Custom { function_calling, .. } => *function_calling,
-			Self::Unknown(_) => false,
+			_ => false,
 		}
 	}
 }
@@ -143,3 +333,57 @@ impl<'de> Deserialize<'de> for Model {
 		Ok(Self::from_id(&trimmed))
 	}
 }
+
+/// Kind of content a model takes or generates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modality {
+	/// Plain text
+	Text,
+	/// Images
+	Image,
+	/// Speech and other audio
+	Audio,
+}
+
+#[test]
+fn model_should_be_recognized() {
+	for model in [
+		Model::Gpt41,
+		Model::Gpt41Mini,
+		Model::Gpt41Nano,
+		Model::O1,
+		Model::O3,
+		Model::O4Mini,
+		Model::Gpt5,
+		Model::Gpt5Mini,
+		Model::Gpt5Nano,
+		Model::GptImage1,
+		Model::Whisper1,
+		Model::Tts1,
+		Model::Tts1Hd,
+	] {
+		assert_eq!(Model::from_id(&model.id()), model);
+	}
+
+	let o3 = serde_json::from_str::<Model>("\"o3-2025-04-16\"").unwrap();
+
+	assert_eq!(o3, Model::O3);
+	assert!(o3.reasoning() && o3.function_calling());
+	assert!(!Model::Gpt41.reasoning());
+	assert!(!Model::Tts1.function_calling());
+	assert_eq!(Model::Gpt5.max_output_tokens(), Some(128_000));
+}
+
+#[test]
+fn model_metadata_should_work() {
+	assert_eq!(Model::Gpt41.context_window(), Some(1_047_576));
+	assert_eq!(Model::O3.input_modalities(), [Modality::Text, Modality::Image]);
+	assert_eq!(Model::Tts1.output_modalities(), [Modality::Audio]);
+	assert!(Model::TextEmbedding3Small.output_modalities().is_empty());
+	assert!(Model::Gpt4o.temperature());
+	assert!(!Model::Gpt5.temperature());
+	assert_eq!(Model::Gpt4o.knowledge_cutoff(), Some("2023-10-01"));
+	assert_eq!(Model::Unknown("foo".into()).knowledge_cutoff(), None);
+	assert_eq!(Model::Gpt4o.pricing(), Some(Pricing::new(2.5, 10.).cached_input(1.25)));
+	assert_eq!(Model::Tts1.pricing(), None);
+}
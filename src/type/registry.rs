@@ -0,0 +1,158 @@
+//! Models registered at runtime, e.g. those of a self-hosted or gateway deployment.
+
+// std
+use std::{
+	collections::HashMap,
+	sync::{LazyLock, RwLock},
+};
+// self
+use crate::{_prelude::*, http::Pricing};
+
+static REGISTRY: LazyLock<RwLock<ModelRegistry>> = LazyLock::new(Default::default);
+
+/// Models looked up by [`Model::from_id`] once [installed](ModelRegistry::install).
+///
+/// Built-in models take precedence, a registered model is returned as [`Model::Custom`].
+#[derive(Clone, Debug, Default)]
+pub struct ModelRegistry {
+	models: HashMap<String, ModelInfo>,
+}
+impl ModelRegistry {
+	/// Creates an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Loads a registry from JSON, a `models` array of [`ModelInfo`].
+	pub fn from_json(json: &str) -> Result<Self> {
+		Ok(serde_json::from_str::<ModelList>(json)?.into())
+	}
+
+	/// Loads a registry from TOML, a `[[models]]` array of [`ModelInfo`].
+	#[cfg(feature = "toml")]
+	pub fn from_toml(toml: &str) -> Result<Self> {
+		Ok(toml::from_str::<ModelList>(toml)?.into())
+	}
+
+	/// Adds `model`, replacing any model with the same ID.
+	pub fn register(mut self, model: ModelInfo) -> Self {
+		self.models.insert(model.id.clone(), model);
+
+		self
+	}
+
+	/// Returns the model with the given ID.
+	pub fn get(&self, id: &str) -> Option<&ModelInfo> {
+		self.models.get(id)
+	}
+
+	/// Makes this registry the one consulted by [`Model::from_id`], replacing the previous one.
+	pub fn install(self) {
+		*REGISTRY.write().expect("lock must succeed; qed") = self;
+	}
+
+	/// Returns the model with the given ID from the installed registry.
+	pub fn lookup(id: &str) -> Option<ModelInfo> {
+		REGISTRY.read().expect("lock must succeed; qed").get(id).cloned()
+	}
+}
+impl From<ModelList> for ModelRegistry {
+	fn from(list: ModelList) -> Self {
+		list.models.into_iter().fold(Self::new(), Self::register)
+	}
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+	models: Vec<ModelInfo>,
+}
+
+/// Description of a registered model.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ModelInfo {
+	/// Identifier sent to the API.
+	pub id: String,
+	/// Human-readable name, the ID if empty.
+	#[serde(default)]
+	pub name: String,
+	/// Whether the model supports text embedding operations.
+	#[serde(default)]
+	pub embedding: bool,
+	/// Whether the model supports reasoning capabilities.
+	#[serde(default)]
+	pub reasoning: bool,
+	/// Whether the model supports function calling.
+	#[serde(default)]
+	pub function_calling: bool,
+	/// Maximum number of input and output tokens per request.
+	pub context_window: Option<u32>,
+	/// Maximum number of tokens generated per response.
+	pub max_output_tokens: Option<u32>,
+	/// Token prices.
+	pub pricing: Option<Pricing>,
+}
+impl ModelInfo {
+	/// Returns the model as a [`Model::Custom`].
+	pub fn model(&self) -> Model {
+		let name = if self.name.is_empty() { &self.id } else { &self.name };
+
+		Model::Custom {
+			id: Cow::Owned(self.id.clone()),
+			name: Cow::Owned(name.clone()),
+			embedding: self.embedding,
+			reasoning: self.reasoning,
+			function_calling: self.function_calling,
+		}
+	}
+}
+
+#[test]
+fn registry_should_work() {
+	let registry = ModelRegistry::from_json(
+		r#"{
+	"models": [
+		{
+			"id": "llama-3-70b",
+			"name": "Llama 3 70B",
+			"function_calling": true,
+			"context_window": 8192,
+			"max_output_tokens": 2048,
+			"pricing": { "input": 0.5, "output": 0.8 }
+		}
+	]
+}"#,
+	)
+	.unwrap();
+
+	assert!(matches!(Model::from_id("llama-3-70b"), Model::Unknown(_)));
+
+	registry.install();
+
+	let model = Model::from_id("llama-3-70b");
+
+	assert_eq!(model.name(), "Llama 3 70B");
+	assert!(model.function_calling() && !model.reasoning());
+	assert_eq!(model.context_window(), Some(8192));
+	assert_eq!(model.max_output_tokens(), Some(2048));
+	assert_eq!(model.pricing().map(|p| p.output), Some(0.8));
+	assert_eq!(Model::from_id("gpt-4o"), Model::Gpt4o);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn registry_should_load_toml() {
+	let registry = ModelRegistry::from_toml(
+		r#"
+[[models]]
+id = "qwen-2.5-72b"
+reasoning = true
+context_window = 131072
+"#,
+	)
+	.unwrap();
+	let info = registry.get("qwen-2.5-72b").unwrap();
+
+	assert_eq!(info.model().name(), "qwen-2.5-72b");
+	assert!(info.reasoning);
+	assert_eq!(info.context_window, Some(131_072));
+}
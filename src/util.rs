@@ -108,44 +108,75 @@ macro_rules! _generate_serializable_enum {
 	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } with_default $default_var:ident) => {
 		crate::util::_define_enum!($name { $($var),* } with_default $default_var);
 		crate::util::_impl_enum_as_str!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_from_str!($name { $($var_val => $val),* });
 		crate::util::_impl_enum_serialize!($name);
 	};
 	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } without_default) => {
 		crate::util::_define_enum!($name { $($var),* });
 		crate::util::_impl_enum_as_str!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_from_str!($name { $($var_val => $val),* });
 		crate::util::_impl_enum_serialize!($name);
 	};
 }
 pub(crate) use _generate_serializable_enum;
 
-macro_rules! _generate_deserializable_enum {
-	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } with_default $default_var:ident) => {
-		crate::util::_define_enum!($name { $($var),* } with_default $default_var);
-		crate::util::_impl_enum_deserialize!($name { $($var_val => $val),* });
-	};
-	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } without_default) => {
-		crate::util::_define_enum!($name { $($var),* });
-		crate::util::_impl_enum_deserialize!($name { $($var_val => $val),* });
-	};
-}
-pub(crate) use _generate_deserializable_enum;
-
 macro_rules! _generate_serializable_deserializable_enum {
 	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } with_default $default_var:ident) => {
 		crate::util::_define_enum!($name { $($var),* } with_default $default_var);
 		crate::util::_impl_enum_as_str!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_from_str!($name { $($var_val => $val),* });
 		crate::util::_impl_enum_serialize!($name);
-		crate::util::_impl_enum_deserialize!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_deserialize!($name);
 	};
 	($name:ident { $($var:ident),* } { $($var_val:ident => $val:expr),* } without_default) => {
 		crate::util::_define_enum!($name { $($var),* });
 		crate::util::_impl_enum_as_str!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_from_str!($name { $($var_val => $val),* });
 		crate::util::_impl_enum_serialize!($name);
-		crate::util::_impl_enum_deserialize!($name { $($var_val => $val),* });
+		crate::util::_impl_enum_deserialize!($name);
 	};
 }
 pub(crate) use _generate_serializable_deserializable_enum;
 
+macro_rules! _generate_enum_with_other {
+	($name:ident { $($var:ident => $val:expr),* }) => {
+		#[allow(missing_docs)]
+		#[derive(Clone, Debug, PartialEq, Eq)]
+		pub enum $name {
+			$($var,)*
+			/// Value unknown to this crate, kept as received.
+			Other(String),
+		}
+		impl $name {
+			#[allow(missing_docs)]
+			pub fn as_str(&self) -> &str {
+				match self {
+					$(Self::$var => $val,)*
+					Self::Other(s) => s,
+				}
+			}
+		}
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str(self.as_str())
+			}
+		}
+		impl std::str::FromStr for $name {
+			type Err = std::convert::Infallible;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				match s {
+					$($val => Ok(Self::$var),)*
+					_ => Ok(Self::Other(s.to_owned())),
+				}
+			}
+		}
+		crate::util::_impl_enum_serialize!($name);
+		crate::util::_impl_enum_deserialize!($name);
+	};
+}
+pub(crate) use _generate_enum_with_other;
+
 macro_rules! _impl_enum_as_str {
 	($name:ident { $($var:ident => $val:expr),* $(,)? }) => {
 		impl $name {
@@ -156,10 +187,31 @@ macro_rules! _impl_enum_as_str {
 				}
 			}
 		}
+		impl std::fmt::Display for $name {
+			fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str(self.as_str())
+			}
+		}
 	};
 }
 pub(crate) use _impl_enum_as_str;
 
+macro_rules! _impl_enum_from_str {
+	($name:ident { $($var:ident => $val:expr),* $(,)? }) => {
+		impl std::str::FromStr for $name {
+			type Err = crate::error::Error;
+
+			fn from_str(s: &str) -> Result<Self, Self::Err> {
+				match s {
+					$($val => Ok(Self::$var),)*
+					_ => Err(crate::error::Error::any(format!("unknown variant: {s}"))),
+				}
+			}
+		}
+	};
+}
+pub(crate) use _impl_enum_from_str;
+
 macro_rules! _impl_enum_serialize {
 	($name:ident) => {
 		impl serde::Serialize for $name {
@@ -175,17 +227,13 @@ macro_rules! _impl_enum_serialize {
 pub(crate) use _impl_enum_serialize;
 
 macro_rules! _impl_enum_deserialize {
-	($name:ident { $($var:ident => $val:expr),* $(,)? }) => {
+	($name:ident) => {
 		impl<'de> serde::Deserialize<'de> for $name {
 			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 			where
 				D: serde::Deserializer<'de>,
 			{
-				let s = String::deserialize(deserializer)?;
-				match s.as_str() {
-					$($val => Ok(Self::$var),)*
-					_ => Err(serde::de::Error::custom(format!("unknown variant: {s}"))),
-				}
+				String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
 			}
 		}
 	};
@@ -209,24 +257,20 @@ macro_rules! impl_serializable_enum {
 }
 pub(crate) use impl_serializable_enum;
 
-macro_rules! impl_deserializable_enum {
+macro_rules! impl_serializable_deserializable_enum {
+	// Enums marked `#[other]` keep unknown values in an `Other(String)` variant.
 	{
 		$(
+			#[other]
 			$name:ident {
-				$($content:tt)*
+				$($var:ident => $val:expr),* $(,)?
 			}
 		)*
 	} => {
 		$(
-			crate::util::_parse_enum_with_default!(
-				$name { $($content)* } => _generate_deserializable_enum
-			);
+			crate::util::_generate_enum_with_other!($name { $($var => $val),* });
 		)*
 	};
-}
-pub(crate) use impl_deserializable_enum;
-
-macro_rules! impl_serializable_deserializable_enum {
 	{
 		$(
 			$name:ident {
@@ -351,4 +395,33 @@ mod tests {
 		let status = TestStatus3::default();
 		assert_eq!(status, TestStatus3::Third);
 	}
+
+	impl_serializable_deserializable_enum! {
+		#[other]
+		TestStatus4 {
+			First => "first",
+			Second => "second",
+		}
+	}
+
+	#[test]
+	fn other_should_work() {
+		let deserialized: TestStatus4 = serde_json::from_str("\"first\"").unwrap();
+		assert_eq!(deserialized, TestStatus4::First);
+
+		let deserialized: TestStatus4 = serde_json::from_str("\"third\"").unwrap();
+		assert_eq!(deserialized, TestStatus4::Other("third".into()));
+
+		let serialized = serde_json::to_string(&deserialized).unwrap();
+		assert_eq!(serialized, "\"third\"");
+	}
+
+	#[test]
+	fn display_and_from_str_should_work() {
+		assert_eq!(TestStatus::Pending.to_string(), "pending");
+		assert_eq!("inactive".parse::<TestStatus>().unwrap(), TestStatus::Inactive);
+		assert!("unknown".parse::<TestStatus>().is_err());
+		assert_eq!(TestStatus4::Other("third".into()).to_string(), "third");
+		assert_eq!("second".parse::<TestStatus4>().unwrap(), TestStatus4::Second);
+	}
 }